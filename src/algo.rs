@@ -0,0 +1,388 @@
+//! Algorithms on trees.
+
+use crate::iter::Edge;
+use crate::{NodeMap, NodeRef};
+
+/// Operation costed by [`edit_distance`].
+#[derive(Debug)]
+pub enum Operation<'a, T> {
+    /// Inserts a node of the second tree.
+    Insert(NodeRef<'a, T>),
+    /// Deletes a node of the first tree.
+    Delete(NodeRef<'a, T>),
+    /// Relabels a node of the first tree as a node of the second tree.
+    Relabel(NodeRef<'a, T>, NodeRef<'a, T>),
+}
+
+impl<'a, T> Clone for Operation<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<'a, T> Copy for Operation<'a, T> {}
+
+/// Cost function of [`edit_distance`] where inserting and deleting cost 1,
+/// and relabeling costs 1 between different values.
+pub fn unit_cost<T: PartialEq>(operation: Operation<T>) -> u64 {
+    match operation {
+        Operation::Relabel(a, b) if a.value() == b.value() => 0,
+        _ => 1,
+    }
+}
+
+/// Returns the ordered tree edit distance between the subtrees of `a` and `b`.
+///
+/// The distance is the minimum total cost of inserting, deleting and
+/// relabeling nodes to turn one subtree into the other, where deleting a node
+/// moves its children to its parent and inserting a node does the opposite.
+/// It is computed with the Zhang–Shasha algorithm, in `O(n²m²)` time in the
+/// worst case and `O(nm)` space, for subtrees of `n` and `m` nodes.
+///
+/// # Examples
+///
+/// ```
+/// # use ego_tree::tree;
+/// use ego_tree::algo::{edit_distance, unit_cost};
+///
+/// let a = tree!('f' => { 'd' => { 'a', 'c' => { 'b' } }, 'e' });
+/// let b = tree!('f' => { 'c' => { 'd' => { 'a', 'b' } }, 'e' });
+/// assert_eq!(2, edit_distance(a.root(), b.root(), unit_cost));
+/// ```
+pub fn edit_distance<'a, T, F>(a: NodeRef<'a, T>, b: NodeRef<'a, T>, cost: F) -> u64
+where
+    F: FnMut(Operation<'a, T>) -> u64,
+{
+    let mut zs = ZhangShasha::new(a, b, cost);
+    zs.tree_distances();
+    zs.tree_distance(zs.a.len(), zs.b.len())
+}
+
+/// Result of [`edit_mapping`].
+#[derive(Debug)]
+pub struct EditMapping<'a, T> {
+    /// The edit distance.
+    pub distance: u64,
+    /// The pairs of nodes kept by a minimum-cost edit, in post-order.
+    ///
+    /// Kept nodes are relabeled, possibly at no cost. Other nodes of the first
+    /// subtree are deleted and other nodes of the second one are inserted.
+    pub pairs: Vec<(NodeRef<'a, T>, NodeRef<'a, T>)>,
+}
+
+/// Returns the ordered tree edit distance between the subtrees of `a` and `b`,
+/// with the pairs of nodes kept by a minimum-cost edit.
+///
+/// See [`edit_distance`].
+pub fn edit_mapping<'a, T, F>(a: NodeRef<'a, T>, b: NodeRef<'a, T>, cost: F) -> EditMapping<'a, T>
+where
+    F: FnMut(Operation<'a, T>) -> u64,
+{
+    let mut zs = ZhangShasha::new(a, b, cost);
+    zs.tree_distances();
+
+    // Backtracks through the forest distances of each pair of subtrees
+    // involved in the result.
+    let mut pairs = Vec::new();
+    let mut stack = vec![(zs.a.len(), zs.b.len())];
+    while let Some((i, j)) = stack.pop() {
+        let (li, lj) = (zs.a.leftmost[i], zs.b.leftmost[j]);
+        let forest = zs.forest_distances(i, j);
+        let width = j - lj + 2;
+        let fd = |x: usize, y: usize| forest[(x + 1 - li) * width + (y + 1 - lj)];
+        let (mut x, mut y) = (i, j);
+        while x >= li || y >= lj {
+            if x >= li && (y < lj || fd(x, y) == fd(x - 1, y) + zs.a.costs[x]) {
+                x -= 1;
+            } else if y >= lj && (x < li || fd(x, y) == fd(x, y - 1) + zs.b.costs[y]) {
+                y -= 1;
+            } else if zs.a.leftmost[x] == li && zs.b.leftmost[y] == lj {
+                pairs.push((x, y));
+                x -= 1;
+                y -= 1;
+            } else {
+                stack.push((x, y));
+                x = zs.a.leftmost[x] - 1;
+                y = zs.b.leftmost[y] - 1;
+            }
+        }
+    }
+
+    pairs.sort_unstable();
+    let distance = zs.tree_distance(zs.a.len(), zs.b.len());
+    let pairs = pairs
+        .into_iter()
+        .map(|(x, y)| (zs.a.nodes[x], zs.b.nodes[y]))
+        .collect();
+    EditMapping { distance, pairs }
+}
+
+/// Returns true if the subtrees of `a` and `b` are isomorphic as unordered
+/// trees, that is, if they are equal up to the order of siblings.
+///
+/// Subtrees are compared by their AHU canonical forms, in `O(n log n)` time
+/// for subtrees of `n` nodes in total.
+///
+/// # Examples
+///
+/// ```
+/// # use ego_tree::tree;
+/// use ego_tree::algo::is_isomorphic_unordered;
+///
+/// let a = tree!('a' => { 'b' => { 'c', 'd' }, 'e' });
+/// let b = tree!('a' => { 'e', 'b' => { 'd', 'c' } });
+/// assert!(is_isomorphic_unordered(a.root(), b.root()));
+/// ```
+pub fn is_isomorphic_unordered<T: Ord>(a: NodeRef<T>, b: NodeRef<T>) -> bool {
+    let forms = CanonicalForms::new(&[a, b]);
+    forms.ranks[forms.roots[0]] == forms.ranks[forms.roots[1]]
+}
+
+/// Returns the rank of the canonical form of the subtree of each node of the
+/// subtree of `root`.
+///
+/// Canonical forms are ordered by height, then by value, then by the sorted
+/// canonical forms of the children. Equal ranks mean isomorphic subtrees as
+/// unordered trees. Sorting the children of every node by rank puts the
+/// subtree in a canonical order, so that two subtrees are isomorphic as
+/// unordered trees if and only if they are equal once sorted.
+///
+/// # Examples
+///
+/// ```
+/// # use ego_tree::tree;
+/// use ego_tree::algo::canonical_ranks;
+///
+/// use ego_tree::Tree;
+///
+/// fn normalize(tree: &mut Tree<char>) {
+///     let ranks = canonical_ranks(tree.root());
+///     let ids: Vec<_> = tree.root().descendants().map(|node| node.id()).collect();
+///     for id in ids {
+///         let mut node = tree.get_mut(id).unwrap();
+///         node.sort_children_by_key(|child| ranks[child.id()]);
+///     }
+/// }
+///
+/// let mut a = tree!('a' => { 'b' => { 'c', 'd' }, 'e' });
+/// let mut b = tree!('a' => { 'e', 'b' => { 'd', 'c' } });
+/// normalize(&mut a);
+/// normalize(&mut b);
+/// assert!(a.structural_eq(&b));
+/// ```
+pub fn canonical_ranks<T: Ord>(root: NodeRef<T>) -> NodeMap<usize> {
+    let forms = CanonicalForms::new(&[root]);
+    forms
+        .nodes
+        .iter()
+        .zip(forms.ranks)
+        .map(|(node, rank)| (node.id(), rank))
+        .collect()
+}
+
+/// AHU canonical forms of the subtrees of some nodes.
+struct CanonicalForms<'a, T> {
+    /// Nodes, in post-order of each subtree in turn.
+    nodes: Vec<NodeRef<'a, T>>,
+    /// Rank of the canonical form of each node.
+    ranks: Vec<usize>,
+    /// Index of each root.
+    roots: Vec<usize>,
+}
+
+impl<'a, T: Ord> CanonicalForms<'a, T> {
+    fn new(roots: &[NodeRef<'a, T>]) -> Self {
+        let mut nodes = Vec::new();
+        let mut children: Vec<Vec<usize>> = Vec::new();
+        let mut levels: Vec<Vec<usize>> = Vec::new();
+        let mut root_indices = Vec::new();
+        // Indices and heights of the children of the open nodes.
+        let mut closed = Vec::new();
+        let mut open = Vec::new();
+        for &root in roots {
+            for edge in root.traverse() {
+                match edge {
+                    Edge::Open(_) => open.push(closed.len()),
+                    Edge::Close(node) => {
+                        let start = open.pop().unwrap();
+                        let index = nodes.len();
+                        let height = closed[start..]
+                            .iter()
+                            .map(|&(_, height)| height + 1)
+                            .max()
+                            .unwrap_or(0);
+                        nodes.push(node);
+                        children.push(closed.drain(start..).map(|(child, _)| child).collect());
+                        if levels.len() <= height {
+                            levels.resize_with(height + 1, Vec::new);
+                        }
+                        levels[height].push(index);
+                        closed.push((index, height));
+                    }
+                }
+            }
+            root_indices.push(closed.pop().unwrap().0);
+        }
+
+        // Ranks nodes level by level from the leaves, so the ranks of the
+        // children of a level are known and lower than those of the level.
+        let mut ranks = vec![0; nodes.len()];
+        let mut next_rank = 0;
+        for level in levels {
+            let mut keys: Vec<(&T, Vec<usize>, usize)> = level
+                .into_iter()
+                .map(|index| {
+                    let mut child_ranks: Vec<usize> =
+                        children[index].iter().map(|&child| ranks[child]).collect();
+                    child_ranks.sort_unstable();
+                    (nodes[index].value(), child_ranks, index)
+                })
+                .collect();
+            keys.sort_unstable_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+            for (i, (value, child_ranks, index)) in keys.iter().enumerate() {
+                if i > 0 && (keys[i - 1].0, &keys[i - 1].1) != (*value, child_ranks) {
+                    next_rank += 1;
+                }
+                ranks[*index] = next_rank;
+            }
+            next_rank += 1;
+        }
+
+        CanonicalForms {
+            nodes,
+            ranks,
+            roots: root_indices,
+        }
+    }
+}
+
+/// Subtree numbered in post-order, from 1.
+struct PostOrder<'a, T> {
+    /// Nodes, with the root of the subtree repeated at index 0.
+    nodes: Vec<NodeRef<'a, T>>,
+    /// Index of the leftmost leaf descendant of each node.
+    leftmost: Vec<usize>,
+    /// Nodes that are the root or have a left sibling, in post-order.
+    keyroots: Vec<usize>,
+    /// Cost of deleting or inserting each node.
+    costs: Vec<u64>,
+}
+
+impl<'a, T> PostOrder<'a, T> {
+    fn new(root: NodeRef<'a, T>, mut cost: impl FnMut(NodeRef<'a, T>) -> u64) -> Self {
+        let mut nodes = vec![root];
+        let mut leftmost = vec![0];
+        let mut keyroots = Vec::new();
+        let mut costs = vec![0];
+        // Leftmost leaves of the open nodes.
+        let mut open = Vec::new();
+        for edge in root.traverse() {
+            match edge {
+                Edge::Open(_) => open.push(nodes.len()),
+                Edge::Close(node) => {
+                    let index = nodes.len();
+                    let first = open.pop().unwrap();
+                    nodes.push(node);
+                    leftmost.push(first);
+                    costs.push(cost(node));
+                    if node == root || node.prev_sibling().is_some() {
+                        keyroots.push(index);
+                    }
+                }
+            }
+        }
+        PostOrder {
+            nodes,
+            leftmost,
+            keyroots,
+            costs,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.nodes.len() - 1
+    }
+}
+
+struct ZhangShasha<'a, T> {
+    a: PostOrder<'a, T>,
+    b: PostOrder<'a, T>,
+    /// Cost of relabeling each pair of nodes, by index.
+    relabel: Vec<u64>,
+    /// Distance between each pair of subtrees, by index.
+    trees: Vec<u64>,
+}
+
+impl<'a, T> ZhangShasha<'a, T> {
+    fn new<F>(a: NodeRef<'a, T>, b: NodeRef<'a, T>, mut cost: F) -> Self
+    where
+        F: FnMut(Operation<'a, T>) -> u64,
+    {
+        let a = PostOrder::new(a, |node| cost(Operation::Delete(node)));
+        let b = PostOrder::new(b, |node| cost(Operation::Insert(node)));
+        let width = b.len() + 1;
+        let mut relabel = vec![0; (a.len() + 1) * width];
+        for x in 1..=a.len() {
+            for y in 1..=b.len() {
+                relabel[x * width + y] = cost(Operation::Relabel(a.nodes[x], b.nodes[y]));
+            }
+        }
+        let trees = vec![0; relabel.len()];
+        ZhangShasha {
+            a,
+            b,
+            relabel,
+            trees,
+        }
+    }
+
+    fn tree_distance(&self, x: usize, y: usize) -> u64 {
+        self.trees[x * (self.b.len() + 1) + y]
+    }
+
+    /// Computes the distances between all pairs of subtrees.
+    fn tree_distances(&mut self) {
+        for i in 0..self.a.keyroots.len() {
+            for j in 0..self.b.keyroots.len() {
+                self.forest_distances(self.a.keyroots[i], self.b.keyroots[j]);
+            }
+        }
+    }
+
+    /// Returns the distances between the forests of the nodes from the
+    /// leftmost leaves of `i` and `j` up to each pair of nodes of their
+    /// subtrees, recording the distances between subtrees along the way.
+    ///
+    /// The distances between the subtrees below `i` and `j` that are not on
+    /// their leftmost paths must already be known.
+    fn forest_distances(&mut self, i: usize, j: usize) -> Vec<u64> {
+        let (li, lj) = (self.a.leftmost[i], self.b.leftmost[j]);
+        let width = j - lj + 2;
+        let tree_width = self.b.len() + 1;
+        let mut forest = vec![0; (i - li + 2) * width];
+        let index = |x: usize, y: usize| (x + 1 - li) * width + (y + 1 - lj);
+
+        for x in li..=i {
+            forest[index(x, lj - 1)] = forest[index(x - 1, lj - 1)] + self.a.costs[x];
+        }
+        for y in lj..=j {
+            forest[index(li - 1, y)] = forest[index(li - 1, y - 1)] + self.b.costs[y];
+        }
+        for x in li..=i {
+            for y in lj..=j {
+                let delete = forest[index(x - 1, y)] + self.a.costs[x];
+                let insert = forest[index(x, y - 1)] + self.b.costs[y];
+                let (lx, ly) = (self.a.leftmost[x], self.b.leftmost[y]);
+                forest[index(x, y)] = if lx == li && ly == lj {
+                    let relabel = forest[index(x - 1, y - 1)] + self.relabel[x * tree_width + y];
+                    let distance = delete.min(insert).min(relabel);
+                    self.trees[x * tree_width + y] = distance;
+                    distance
+                } else {
+                    let subtrees = forest[index(lx - 1, ly - 1)] + self.trees[x * tree_width + y];
+                    delete.min(insert).min(subtrees)
+                };
+            }
+        }
+        forest
+    }
+}
@@ -0,0 +1,96 @@
+//! Mermaid and PlantUML diagram export.
+//!
+//! Nodes are visited in the same order as the `Display` output of a tree.
+//!
+//! # Examples
+//!
+//! ```
+//! # use ego_tree::tree;
+//! use ego_tree::diagram::PlantUml;
+//!
+//! let tree = tree!('a' => { 'b', 'c' => { 'd' } });
+//! let label = |node: ego_tree::NodeRef<char>| node.value().to_string();
+//! assert_eq!(
+//!     tree.root().to_mermaid(label),
+//!     "graph TD\n    n0[\"a\"]\n    n0 --> n1[\"b\"]\n    n0 --> n2[\"c\"]\n    n2 --> n3[\"d\"]\n",
+//! );
+//! assert_eq!(
+//!     tree.root().to_plantuml(PlantUml::MindMap, label),
+//!     "@startmindmap\n* a\n** b\n** c\n*** d\n@endmindmap\n",
+//! );
+//! ```
+
+use std::fmt::Write;
+
+use crate::display::visit;
+use crate::NodeRef;
+
+/// Kind of PlantUML diagram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlantUml {
+    /// Mind map, `@startmindmap`.
+    MindMap,
+    /// Work breakdown structure, `@startwbs`.
+    Wbs,
+}
+
+impl<'a, T: 'a> NodeRef<'a, T> {
+    /// Returns the subtree of this node as a Mermaid `graph TD` block,
+    /// labeling nodes with `label`.
+    ///
+    /// Nodes are named after the index of their `NodeId`.
+    pub fn to_mermaid<F>(&self, mut label: F) -> String
+    where
+        F: FnMut(NodeRef<'a, T>) -> String,
+    {
+        let root = *self;
+        let mut mermaid = String::from("graph TD\n");
+        visit(root, |node, _| {
+            let id = node.id.to_index();
+            mermaid.push_str("    ");
+            if node != root {
+                let parent_id = node.parent().unwrap().id.to_index();
+                write!(mermaid, "n{parent_id} --> ")?;
+            }
+            write!(mermaid, "n{id}[\"")?;
+            for c in label(node).chars() {
+                match c {
+                    '"' => mermaid.push_str("#quot;"),
+                    '#' => mermaid.push_str("#35;"),
+                    '\n' => mermaid.push_str("<br>"),
+                    '\r' => {}
+                    c => mermaid.push(c),
+                }
+            }
+            mermaid.push_str("\"]\n");
+            Ok(())
+        })
+        .expect("writing to a String cannot fail");
+        mermaid
+    }
+
+    /// Returns the subtree of this node as a PlantUML diagram, labeling nodes
+    /// with `label`.
+    pub fn to_plantuml<F>(&self, diagram: PlantUml, mut label: F) -> String
+    where
+        F: FnMut(NodeRef<'a, T>) -> String,
+    {
+        let name = match diagram {
+            PlantUml::MindMap => "mindmap",
+            PlantUml::Wbs => "wbs",
+        };
+        let mut plantuml = format!("@start{name}\n");
+        visit(*self, |node, depth| {
+            let stars = "*".repeat(depth + 1);
+            let label = label(node);
+            if label.contains('\n') {
+                writeln!(plantuml, "{stars}:{};", label.replace('\r', ""))
+            } else {
+                writeln!(plantuml, "{stars} {label}")
+            }
+        })
+        .expect("writing to a String cannot fail");
+        writeln!(plantuml, "@end{name}").expect("writing to a String cannot fail");
+        plantuml
+    }
+}
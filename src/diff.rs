@@ -0,0 +1,549 @@
+//! Structural diffs between trees.
+//!
+//! An edit script refers to nodes by their ID in the tree it edits. Inserted
+//! nodes are given the IDs they get when the script is applied: the `k`-th
+//! insert of a script, counting from 0, gets the ID of index `len + k`, where
+//! `len` is the number of nodes of the edited tree, including orphans.
+//!
+//! Edits and errors display IDs in the opaque `Debug` form of [`NodeId`], as
+//! elsewhere in the crate. That form is not the index of the node: the ID of
+//! index 0, the root of a new tree, displays as `NodeId(1)`.
+//!
+//! Only the subtrees of the roots are compared. Orphans are ignored.
+//!
+//! # Examples
+//!
+//! ```
+//! # use ego_tree::tree;
+//! let a = tree!("root" => { "a", "b" => { "c" } });
+//! let b = tree!("root" => { "b" => { "c", "d" } });
+//! let edits = ego_tree::diff_by_key(&a, &b, |node| *node.value());
+//! assert_eq!(
+//!     a.display_diff(&edits).unwrap().to_string(),
+//!     "  root\n  ├── b\n  │   ├── c\n+ │   └── d\n- └── a\n",
+//! );
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+
+use crate::display::DisplayOptions;
+use crate::{Node, NodeId, NodeRef, Tree};
+
+/// Operation of an edit script.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Edit<T> {
+    /// Inserts a new node as the child at `index` of `parent`.
+    Insert {
+        /// ID of the new node.
+        id: NodeId,
+        /// Parent of the new node.
+        parent: NodeId,
+        /// Index of the new node among its siblings.
+        index: usize,
+        /// Value of the new node.
+        value: T,
+    },
+    /// Detaches the subtree of a node.
+    Delete {
+        /// Root of the subtree.
+        id: NodeId,
+    },
+    /// Replaces the value of a node.
+    Update {
+        /// The node.
+        id: NodeId,
+        /// New value of the node.
+        value: T,
+    },
+    /// Moves the subtree of a node to become the child at `index` of `parent`.
+    Move {
+        /// Root of the subtree.
+        id: NodeId,
+        /// New parent of the node.
+        parent: NodeId,
+        /// Index of the node among its new siblings, once it is detached.
+        index: usize,
+    },
+}
+
+impl<T: Display> Display for Edit<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Edit::Insert {
+                id,
+                parent,
+                index,
+                value,
+            } => write!(f, "insert {:?} at {} in {:?}: {}", id, index, parent, value),
+            Edit::Delete { id } => write!(f, "delete {:?}", id),
+            Edit::Update { id, value } => write!(f, "update {:?}: {}", id, value),
+            Edit::Move { id, parent, index } => {
+                write!(f, "move {:?} to {} in {:?}", id, index, parent)
+            }
+        }
+    }
+}
+
+/// Returns an edit script from `a` to `b`, matching nodes by position.
+///
+/// Children at the same index of matching nodes match, starting from the
+/// roots, so the script never moves nodes.
+pub fn diff<T: PartialEq + Clone>(a: &Tree<T>, b: &Tree<T>) -> Vec<Edit<T>> {
+    let mut partners = vec![None; b.vec.len()];
+    let mut pairs = vec![(a.root(), b.root())];
+    while let Some((a_node, b_node)) = pairs.pop() {
+        partners[b_node.id.to_index()] = Some(a_node.id);
+        pairs.extend(a_node.children().zip(b_node.children()));
+    }
+    script(a, b, &partners)
+}
+
+/// Returns an edit script from `a` to `b`, matching nodes with equal keys.
+///
+/// The roots always match. Other nodes with the same key match in tree
+/// order, wherever they are in their tree, so nodes with unique keys are
+/// moved rather than deleted and inserted again.
+pub fn diff_by_key<T, K, F>(a: &Tree<T>, b: &Tree<T>, mut key: F) -> Vec<Edit<T>>
+where
+    T: PartialEq + Clone,
+    K: Hash + Eq,
+    F: FnMut(NodeRef<T>) -> K,
+{
+    let mut partners = vec![None; b.vec.len()];
+    partners[0] = Some(a.root().id);
+    let a_nodes = a.root().descendants().skip(1);
+    let b_nodes = b.root().descendants().skip(1);
+    for (a_id, b_id) in pair_by_key(a_nodes, b_nodes, &mut key) {
+        partners[b_id.to_index()] = Some(a_id);
+    }
+    script(a, b, &partners)
+}
+
+/// Pairs nodes of `a` and `b` with equal keys, in order, returning their IDs.
+pub(crate) fn pair_by_key<'a, T, K, F>(
+    a: impl IntoIterator<Item = NodeRef<'a, T>>,
+    b: impl IntoIterator<Item = NodeRef<'a, T>>,
+    mut key: F,
+) -> Vec<(NodeId, NodeId)>
+where
+    T: 'a,
+    K: Hash + Eq,
+    F: FnMut(NodeRef<T>) -> K,
+{
+    let mut ids: HashMap<K, VecDeque<NodeId>> = HashMap::new();
+    for node in a {
+        ids.entry(key(node)).or_default().push_back(node.id);
+    }
+    b.into_iter()
+        .filter_map(|node| {
+            let id = ids.get_mut(&key(node))?.pop_front()?;
+            Some((id, node.id))
+        })
+        .collect()
+}
+
+/// Returns an edit script from `a` to `b`, given the node of `a` matching
+/// each node of `b`, by index.
+fn script<T: PartialEq + Clone>(
+    a: &Tree<T>,
+    b: &Tree<T>,
+    partners: &[Option<NodeId>],
+) -> Vec<Edit<T>> {
+    let mut edits = Vec::new();
+    let mut shadow = shadow(a);
+    let mut matched = vec![false; a.vec.len()];
+    for id in partners.iter().flatten() {
+        matched[id.to_index()] = true;
+    }
+
+    if a.root().value() != b.root().value() {
+        edits.push(Edit::Update {
+            id: a.root().id,
+            value: b.root().value().clone(),
+        });
+    }
+
+    // Deletes unmatched subtrees first, so that they do not shift the
+    // positions of their siblings. Matched nodes in them are moved out later.
+    for node in a.root().descendants().skip(1) {
+        let parent = node.parent().unwrap();
+        if !matched[node.id.to_index()] && matched[parent.id.to_index()] {
+            edits.push(Edit::Delete { id: node.id });
+            shadow.get_mut(node.id).unwrap().detach();
+        }
+    }
+
+    // Places the nodes of `b` in tree order, so that the parent and previous
+    // siblings of each node are already in place in the shadow.
+    let mut ids = partners.to_vec();
+    let mut indices = vec![0; b.vec.len()];
+    for node in b.root().descendants().skip(1) {
+        let parent = ids[node.parent().unwrap().id.to_index()].unwrap();
+        let prev = node.prev_sibling().map(|prev| prev.id.to_index());
+        let index = prev.map_or(0, |prev| indices[prev] + 1);
+        let prev = prev.map(|prev| ids[prev].unwrap());
+        indices[node.id.to_index()] = index;
+
+        let id = match partners[node.id.to_index()] {
+            Some(id) => {
+                if a.get(id).unwrap().value() != node.value() {
+                    edits.push(Edit::Update {
+                        id,
+                        value: node.value().clone(),
+                    });
+                }
+                let expected = match prev {
+                    Some(prev) => shadow.get(prev).unwrap().node.next_sibling,
+                    None => shadow.get(parent).unwrap().node.children.map(|(id, _)| id),
+                };
+                if expected == Some(id) {
+                    continue;
+                }
+                edits.push(Edit::Move { id, parent, index });
+                id
+            }
+            None => {
+                let id = shadow.orphan(()).id;
+                edits.push(Edit::Insert {
+                    id,
+                    parent,
+                    index,
+                    value: node.value().clone(),
+                });
+                ids[node.id.to_index()] = Some(id);
+                id
+            }
+        };
+        match prev {
+            Some(prev) => shadow.get_mut(prev).unwrap().insert_id_after(id),
+            None => shadow.get_mut(parent).unwrap().prepend_id(id),
+        };
+    }
+
+    edits
+}
+
+/// Returns a tree with the same structure as `tree` and no values.
+fn shadow<T>(tree: &Tree<T>) -> Tree<()> {
+    let vec = tree
+        .vec
+        .iter()
+        .map(|node| Node {
+            parent: node.parent,
+            prev_sibling: node.prev_sibling,
+            next_sibling: node.next_sibling,
+            children: node.children,
+            value: (),
+        })
+        .collect();
+    Tree { vec }
+}
+
+/// Result of simulating an edit script.
+#[derive(Debug)]
+struct Simulation {
+    /// Structure of the edited tree.
+    shadow: Tree<()>,
+    /// Deleted nodes with their parent at the time of deletion.
+    deleted: Vec<(NodeId, NodeId)>,
+}
+
+/// Simulates applying `edits` to `tree`, checking that each edit applies.
+fn simulate<T>(tree: &Tree<T>, edits: &[Edit<T>]) -> Result<Simulation, PatchError> {
+    let mut shadow = shadow(tree);
+    let mut deleted = Vec::new();
+    for (i, edit) in edits.iter().enumerate() {
+        let error = |reason| PatchError { edit: i, reason };
+        let get = |shadow: &Tree<()>, id| {
+            shadow
+                .get(id)
+                .map(|node| node.id)
+                .ok_or(error(PatchErrorReason::InvalidId(id)))
+        };
+        match *edit {
+            Edit::Insert {
+                id, parent, index, ..
+            } => {
+                let expected = unsafe { NodeId::from_index(shadow.vec.len()) };
+                if id != expected {
+                    return Err(error(PatchErrorReason::UnexpectedId { id, expected }));
+                }
+                let parent = get(&shadow, parent)?;
+                check_index(&shadow, parent, None, index).map_err(error)?;
+                shadow.orphan(());
+                shadow.get_mut(parent).unwrap().insert_id(id, index);
+            }
+            Edit::Delete { id } => {
+                let id = get(&shadow, id)?;
+                if id == shadow.root().id {
+                    return Err(error(PatchErrorReason::Root));
+                }
+                let parent = shadow.get(id).unwrap().parent();
+                let parent = parent.ok_or(error(PatchErrorReason::Orphan(id)))?.id;
+                shadow.get_mut(id).unwrap().detach();
+                deleted.push((id, parent));
+            }
+            Edit::Update { id, .. } => {
+                get(&shadow, id)?;
+            }
+            Edit::Move { id, parent, index } => {
+                let id = get(&shadow, id)?;
+                let parent = get(&shadow, parent)?;
+                if id == shadow.root().id {
+                    return Err(error(PatchErrorReason::Root));
+                }
+                if shadow
+                    .get(parent)
+                    .unwrap()
+                    .ancestors()
+                    .any(|node| node.id == id)
+                    || parent == id
+                {
+                    return Err(error(PatchErrorReason::Cycle));
+                }
+                check_index(&shadow, parent, Some(id), index).map_err(error)?;
+                shadow.get_mut(id).unwrap().detach();
+                shadow.get_mut(parent).unwrap().insert_id(id, index);
+            }
+        }
+    }
+    Ok(Simulation { shadow, deleted })
+}
+
+/// Checks that `index` is at most the number of children of `parent`, not
+/// counting `id`.
+fn check_index(
+    shadow: &Tree<()>,
+    parent: NodeId,
+    id: Option<NodeId>,
+    index: usize,
+) -> Result<(), PatchErrorReason> {
+    let children = shadow.get(parent).unwrap().children();
+    let len = children.filter(|node| Some(node.id) != id).count();
+    if index > len {
+        return Err(PatchErrorReason::InvalidIndex { index, len });
+    }
+    Ok(())
+}
+
+/// Error returned when an edit script does not apply to a tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PatchError {
+    /// Index of the edit that does not apply.
+    pub edit: usize,
+    /// Reason why the edit does not apply.
+    pub reason: PatchErrorReason,
+}
+
+/// Reason why an edit does not apply to a tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PatchErrorReason {
+    /// A node ID is not valid.
+    InvalidId(NodeId),
+    /// An inserted node does not have the next ID of the tree.
+    UnexpectedId {
+        /// ID of the inserted node.
+        id: NodeId,
+        /// Next ID of the tree.
+        expected: NodeId,
+    },
+    /// An index is greater than the number of children of the parent.
+    InvalidIndex {
+        /// The index.
+        index: usize,
+        /// The number of children of the parent.
+        len: usize,
+    },
+    /// The root is deleted or moved.
+    Root,
+    /// An orphan is deleted.
+    Orphan(NodeId),
+    /// A node is moved into its own subtree.
+    Cycle,
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "edit {}: ", self.edit)?;
+        match self.reason {
+            PatchErrorReason::InvalidId(id) => write!(f, "invalid node {:?}", id),
+            PatchErrorReason::UnexpectedId { id, expected } => {
+                write!(f, "inserted node {:?} should be {:?}", id, expected)
+            }
+            PatchErrorReason::InvalidIndex { index, len } => {
+                write!(f, "index {} is greater than {} children", index, len)
+            }
+            PatchErrorReason::Root => write!(f, "cannot delete or move the root"),
+            PatchErrorReason::Orphan(id) => write!(f, "cannot delete orphan {:?}", id),
+            PatchErrorReason::Cycle => write!(f, "cannot move a node into its own subtree"),
+        }
+    }
+}
+
+impl Error for PatchError {}
+
+impl<T: Clone> Tree<T> {
+    /// Applies an edit script, such as one returned by [`diff`] or
+    /// [`diff_by_key`].
+    ///
+    /// Deleted nodes become orphans, and inserted nodes are added to the end
+    /// of the tree, as with [`Tree::orphan`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, leaving the tree unchanged, if an edit does not apply
+    /// after the previous ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ego_tree::tree;
+    /// let mut a = tree!('a' => { 'b', 'c' => { 'd' } });
+    /// let b = tree!('a' => { 'c' => { 'e' }, 'b' });
+    /// a.apply(&ego_tree::diff_by_key(&a, &b, |node| *node.value())).unwrap();
+    /// assert_eq!(format!("{:#?}", a), format!("{:#?}", b));
+    /// ```
+    pub fn apply(&mut self, edits: &[Edit<T>]) -> Result<(), PatchError> {
+        simulate(self, edits)?;
+        for edit in edits {
+            match edit {
+                Edit::Insert {
+                    parent,
+                    index,
+                    value,
+                    ..
+                } => {
+                    let id = self.orphan(value.clone()).id;
+                    self.get_mut(*parent).unwrap().insert_id(id, *index);
+                }
+                Edit::Delete { id } => self.get_mut(*id).unwrap().detach(),
+                Edit::Update { id, value } => {
+                    *self.get_mut(*id).unwrap().value() = value.clone();
+                }
+                Edit::Move { id, parent, index } => {
+                    self.get_mut(*id).unwrap().detach();
+                    self.get_mut(*parent).unwrap().insert_id(*id, *index);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders an edit script as a unified tree diff.
+///
+/// Returned by [`Tree::display_diff`].
+#[derive(Debug)]
+pub struct UnifiedDiff<'a, T> {
+    tree: &'a Tree<T>,
+    edits: &'a [Edit<T>],
+    simulation: Simulation,
+}
+
+impl<T> Tree<T> {
+    /// Returns a `Display` adapter rendering `edits` as a unified diff of this
+    /// tree.
+    ///
+    /// The edited tree is rendered with a marker before each line: `+` for
+    /// inserted nodes, `~` for updated nodes with their old and new values,
+    /// `>` for moved nodes and `-` for deleted subtrees, which are rendered
+    /// under their last parent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `edits` do not apply to this tree.
+    pub fn display_diff<'a>(
+        &'a self,
+        edits: &'a [Edit<T>],
+    ) -> Result<UnifiedDiff<'a, T>, PatchError> {
+        let simulation = simulate(self, edits)?;
+        Ok(UnifiedDiff {
+            tree: self,
+            edits,
+            simulation,
+        })
+    }
+}
+
+impl<'a, T: Display> Display for UnifiedDiff<'a, T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Simulation { shadow, deleted } = &self.simulation;
+
+        let mut values: Vec<Option<&T>> =
+            self.tree.vec.iter().map(|node| Some(&node.value)).collect();
+        values.resize(shadow.vec.len(), None);
+        let mut updates = vec![None; shadow.vec.len()];
+        let mut moved = vec![false; shadow.vec.len()];
+        for edit in self.edits {
+            match edit {
+                Edit::Insert { id, value, .. } => values[id.to_index()] = Some(value),
+                Edit::Update { id, value } => updates[id.to_index()] = Some(value),
+                Edit::Move { id, .. } => moved[id.to_index()] = true,
+                Edit::Delete { .. } => {}
+            }
+        }
+        let mut deleted_children: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        for &(id, parent) in deleted {
+            deleted_children.entry(parent).or_default().push(id);
+        }
+
+        // Builds the tree of rendered lines, with deleted subtrees after the
+        // children of their last parent.
+        let label = |id: NodeId, is_deleted: bool| {
+            let index = id.to_index();
+            let value = values[index].unwrap();
+            let marker = if is_deleted {
+                '-'
+            } else if index >= self.tree.vec.len() {
+                '+'
+            } else if moved[index] {
+                '>'
+            } else if updates[index].is_some() {
+                '~'
+            } else {
+                ' '
+            };
+            match updates[index] {
+                Some(new) if !is_deleted => (marker, format!("{} → {}", value, new)),
+                _ => (marker, value.to_string()),
+            }
+        };
+        let mut lines = Tree::new(label(shadow.root().id, false));
+        let mut stack = vec![(shadow.root().id, lines.root().id, false)];
+        while let Some((id, line_id, is_deleted)) = stack.pop() {
+            let children = shadow
+                .get(id)
+                .unwrap()
+                .children()
+                .map(|node| (node.id, is_deleted));
+            let deleted = deleted_children
+                .get(&id)
+                .into_iter()
+                .flatten()
+                .map(|&id| (id, true));
+            let children: Vec<_> = children.chain(deleted).collect();
+            for &(child, is_deleted) in children.iter().rev() {
+                let line = label(child, is_deleted);
+                let mut parent = lines.get_mut(line_id).unwrap();
+                let child_line_id = parent.prepend(line).id;
+                stack.push((child, child_line_id, is_deleted));
+            }
+        }
+
+        // Each node renders to one line per line of its label.
+        let rendered = lines
+            .display_with_label(DisplayOptions::default(), |node| node.value().1.clone())
+            .to_string();
+        let mut rendered = rendered.lines();
+        for node in lines.root().descendants() {
+            let (marker, label) = node.value();
+            for _ in 0..label.lines().count().max(1) {
+                writeln!(f, "{} {}", marker, rendered.next().unwrap())?;
+            }
+        }
+        Ok(())
+    }
+}
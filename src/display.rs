@@ -1,89 +1,346 @@
-use std::fmt::Display;
-
-/// Indentation token
-#[derive(Debug)]
-struct Token {
-    /// Is followed by a brother
-    siblings: bool,
-    /// Is intermediate while printing children
-    children: bool,
-}
-
-impl Display for Token {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let Token { siblings, children } = self;
-
-        write!(
-            f,
-            "{}",
-            match (siblings, children) {
-                (true, true) => "│   ",
-                (true, false) => "├── ",
-                (false, true) => "    ",
-                (false, false) => "└── ",
+//! Configurable tree rendering.
+//!
+//! # Examples
+//!
+//! ```
+//! # use ego_tree::tree;
+//! use ego_tree::display::{DisplayOptions, Style};
+//!
+//! let tree = tree!("root" => { "a" => { "b" }, "c" });
+//! let options = DisplayOptions {
+//!     style: Style::Ascii,
+//!     ..DisplayOptions::default()
+//! };
+//! assert_eq!(
+//!     tree.display_with(options).to_string(),
+//!     "root\n|-- a\n|   \\-- b\n\\-- c\n",
+//! );
+//! ```
+
+use std::fmt::{self, Debug, Display, Formatter, Write};
+
+use crate::iter::Edge;
+use crate::{NodeRef, Tree};
+
+/// Calls `f` with each node of a subtree and its depth relative to `root`, in
+/// display order.
+pub(crate) fn visit<'a, T: 'a, F>(root: NodeRef<'a, T>, mut f: F) -> fmt::Result
+where
+    F: FnMut(NodeRef<'a, T>, usize) -> fmt::Result,
+{
+    visit_lines(root, None, None, |node, last| match node {
+        Some(node) => f(node, last.len()),
+        None => Ok(()),
+    })
+}
+
+/// Calls `f` with each line of a rendered subtree, in display order.
+///
+/// Each line is a node, or `None` for elided nodes, along with whether it and
+/// each of its ancestors below `root` are the last lines among their
+/// siblings. Children of nodes at `max_depth` and children past
+/// `max_children` are elided with a single line.
+pub(crate) fn visit_lines<'a, T: 'a, F>(
+    root: NodeRef<'a, T>,
+    max_depth: Option<usize>,
+    max_children: Option<usize>,
+    mut f: F,
+) -> fmt::Result
+where
+    F: FnMut(Option<NodeRef<'a, T>>, &[bool]) -> fmt::Result,
+{
+    // The next child to visit and the number of visited children of each
+    // open node.
+    let mut levels: Vec<(Option<NodeRef<'a, T>>, usize)> = Vec::new();
+    let mut last = Vec::new();
+    let mut next = Some(root);
+
+    loop {
+        if let Some(node) = next.take() {
+            f(Some(node), &last)?;
+            if let Some(child) = node.first_child() {
+                if max_depth.is_some_and(|max| levels.len() >= max) {
+                    last.push(true);
+                    f(None, &last)?;
+                    last.pop();
+                } else {
+                    levels.push((Some(child), 0));
+                }
             }
-        )
+        }
+
+        let depth = levels.len();
+        let Some(level) = levels.last_mut() else {
+            return Ok(());
+        };
+        let Some(node) = level.0 else {
+            levels.pop();
+            continue;
+        };
+        let elide = max_children.is_some_and(|max| level.1 >= max);
+        if elide {
+            level.0 = None;
+        } else {
+            level.0 = node.next_sibling();
+            level.1 += 1;
+        }
+
+        last.truncate(depth - 1);
+        last.push(level.0.is_none());
+        if elide {
+            f(None, &last)?;
+        } else {
+            next = Some(node);
+        }
     }
 }
 
-impl Token {
-    /// Create a new indentation token
-    fn new(siblings: bool) -> Self {
-        Token {
-            siblings,
-            children: false,
+/// Writes a subtree in the syntax of the `tree!` macro.
+pub(crate) fn fmt_macro<T: Debug>(root: NodeRef<T>, f: &mut Formatter) -> fmt::Result {
+    write!(f, "Tree {{")?;
+    for edge in root.traverse() {
+        let last = node_is_last(edge, root);
+        match edge {
+            Edge::Open(node) if node.has_children() => {
+                write!(f, " {:?} => {{", node.value())?;
+            }
+            Edge::Open(node) if !last => {
+                write!(f, " {:?},", node.value())?;
+            }
+            Edge::Open(node) => {
+                write!(f, " {:?}", node.value())?;
+            }
+            Edge::Close(node) if node.has_children() => {
+                if !last {
+                    write!(f, " }},")?;
+                } else {
+                    write!(f, " }}")?;
+                }
+            }
+            _ => {}
         }
     }
+    write!(f, " }}")
+}
 
-    /// Set children flag before starting displaying children
-    fn set_children(&mut self) {
-        self.children = true;
-    }
+/// Returns true if the node of an edge is the root or the last of its
+/// siblings.
+fn node_is_last<T>(edge: Edge<T>, root: NodeRef<T>) -> bool {
+    let node = match edge {
+        Edge::Open(node) | Edge::Close(node) => node,
+    };
+    node == root || node.next_sibling().is_none()
+}
+
+/// Characters used to draw the branches of a tree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Style {
+    /// `│ ├── └──`
+    #[default]
+    Unicode,
+    /// `| |-- \--`
+    Ascii,
+    /// `│ ├── ╰──`
+    Rounded,
+    /// `┃ ┣━━ ┗━━`
+    Heavy,
 }
 
-/// Manages the state during the display operation
-#[derive(Debug)]
-pub struct Indentation {
-    tokens: Vec<Token>,
-    ignore_root: bool,
+impl Style {
+    /// Returns the vertical, branch, last branch and horizontal characters.
+    fn chars(self) -> (char, char, char, char) {
+        match self {
+            Style::Unicode => ('│', '├', '└', '─'),
+            Style::Ascii => ('|', '|', '\\', '-'),
+            Style::Rounded => ('│', '├', '╰', '─'),
+            Style::Heavy => ('┃', '┣', '┗', '━'),
+        }
+    }
 }
 
-impl Display for Indentation {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let first: usize = if self.ignore_root { 1 } else { 0 };
+/// Options for rendering a tree.
+///
+/// The default options render a tree like its `Display` implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DisplayOptions {
+    /// Characters used to draw branches.
+    pub style: Style,
+    /// Width of each level of indentation. Widths below 2 are treated as 2.
+    pub indent_width: usize,
+    /// Renders the root, or only its descendants if false.
+    pub show_root: bool,
+    /// Maximum depth of rendered nodes below the root, if any.
+    ///
+    /// Children of nodes at the maximum depth are elided with `…`.
+    pub max_depth: Option<usize>,
+    /// Maximum number of rendered children per node, if any.
+    ///
+    /// Further children are elided with `…`.
+    pub max_children: Option<usize>,
+}
 
-        for token in &self.tokens[first..] {
-            write!(f, "{token}")?;
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        DisplayOptions {
+            style: Style::Unicode,
+            indent_width: 4,
+            show_root: true,
+            max_depth: None,
+            max_children: None,
         }
+    }
+}
 
-        Ok(())
+/// Renders a tree with [`DisplayOptions`] through its `Display`
+/// implementation.
+///
+/// Returned by [`Tree::display_with`] and [`Tree::display_with_label`].
+pub struct TreeFormatter<'a, T, F> {
+    root: NodeRef<'a, T>,
+    options: DisplayOptions,
+    label: F,
+}
+
+impl<'a, T, F> Debug for TreeFormatter<'a, T, F> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("TreeFormatter")
+            .field("root", &self.root.id())
+            .field("options", &self.options)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Display> Tree<T> {
+    /// Returns a `Display` adapter rendering the tree with `options`,
+    /// labeling nodes with their value.
+    pub fn display_with(
+        &self,
+        options: DisplayOptions,
+    ) -> TreeFormatter<'_, T, fn(NodeRef<'_, T>) -> String> {
+        self.root().display_with(options)
+    }
+}
+
+impl<T> Tree<T> {
+    /// Returns a `Display` adapter rendering the tree with `options`,
+    /// labeling nodes with `label`.
+    pub fn display_with_label<'a, F>(
+        &'a self,
+        options: DisplayOptions,
+        label: F,
+    ) -> TreeFormatter<'a, T, F>
+    where
+        F: Fn(NodeRef<'a, T>) -> String,
+    {
+        self.root().display_with_label(options, label)
+    }
+}
+
+impl<'a, T: Display + 'a> NodeRef<'a, T> {
+    /// Returns a `Display` adapter rendering the subtree of this node with
+    /// `options`, labeling nodes with their value.
+    pub fn display_with(
+        &self,
+        options: DisplayOptions,
+    ) -> TreeFormatter<'a, T, fn(NodeRef<'_, T>) -> String> {
+        self.display_with_label(options, |node| node.value().to_string())
     }
 }
 
-impl Indentation {
-    /// Creates a new indentation handler
-    pub fn new(ignore_root: bool) -> Self {
-        Indentation {
-            tokens: Vec::new(),
-            ignore_root,
+impl<'a, T: 'a> NodeRef<'a, T> {
+    /// Returns a `Display` adapter rendering the subtree of this node with
+    /// `options`, labeling nodes with `label`.
+    pub fn display_with_label<F>(
+        &self,
+        options: DisplayOptions,
+        label: F,
+    ) -> TreeFormatter<'a, T, F>
+    where
+        F: Fn(NodeRef<'a, T>) -> String,
+    {
+        TreeFormatter {
+            root: *self,
+            options,
+            label,
         }
     }
+}
+
+impl<'a, T, F> Display for TreeFormatter<'a, T, F>
+where
+    F: Fn(NodeRef<'a, T>) -> String,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let DisplayOptions {
+            max_depth,
+            max_children,
+            show_root,
+            ..
+        } = self.options;
+        visit_lines(self.root, max_depth, max_children, |node, last| {
+            if last.is_empty() && !show_root {
+                return Ok(());
+            }
+            self.write_prefix(f, last, true)?;
+            match node {
+                Some(node) => self.write_label(f, node, last),
+                None => writeln!(f, "…"),
+            }
+        })
+    }
+}
 
-    /// Adds a new layer of indentation
-    pub fn indent(&mut self, siblings: bool) -> &mut Self {
-        // Setup children mode for previous tokens
-        let len = self.tokens.len();
-        if len > 0 {
-            self.tokens[len - 1].set_children();
+impl<'a, T, F> TreeFormatter<'a, T, F> {
+    /// Writes the label of a node after its prefix.
+    ///
+    /// Continuation lines of a multi-line label are indented under the node,
+    /// with a gutter for its children if it has any.
+    fn write_label(&self, f: &mut Formatter, node: NodeRef<'a, T>, last: &[bool]) -> fmt::Result
+    where
+        F: Fn(NodeRef<'a, T>) -> String,
+    {
+        let label = (self.label)(node);
+        let mut lines = label.lines();
+        writeln!(f, "{}", lines.next().unwrap_or_default())?;
+        for line in lines {
+            self.write_prefix(f, last, false)?;
+            self.write_gutter(f, !node.has_children())?;
+            writeln!(f, "{}", line)?;
         }
+        Ok(())
+    }
 
-        self.tokens.push(Token::new(siblings));
-        self
+    /// Writes the prefix of a line, given whether the node and each of its
+    /// ancestors below the root are the last lines among their siblings.
+    ///
+    /// The prefix ends with a branch to the node if `branch` is true, or only
+    /// continues the gutter otherwise.
+    fn write_prefix(&self, f: &mut Formatter, last: &[bool], branch: bool) -> fmt::Result {
+        let (_, branch_char, last_branch, horizontal) = self.options.style.chars();
+        let width = self.options.indent_width.max(2);
+        let skip = usize::from(!self.options.show_root);
+        for (i, &is_last) in last.iter().enumerate().skip(skip) {
+            if !branch || i + 1 < last.len() {
+                self.write_gutter(f, is_last)?;
+            } else {
+                f.write_char(if is_last { last_branch } else { branch_char })?;
+                for _ in 2..width {
+                    f.write_char(horizontal)?;
+                }
+                f.write_char(' ')?;
+            }
+        }
+        Ok(())
     }
 
-    /// Removes the last layer of indentation
-    pub fn deindent(&mut self) -> &mut Self {
-        self.tokens.pop();
-        self
+    /// Writes one level of gutter, with a vertical line unless the node of
+    /// that level is the last line among its siblings.
+    fn write_gutter(&self, f: &mut Formatter, is_last: bool) -> fmt::Result {
+        let (vertical, ..) = self.options.style.chars();
+        f.write_char(if is_last { ' ' } else { vertical })?;
+        for _ in 1..self.options.indent_width.max(2) {
+            f.write_char(' ')?;
+        }
+        Ok(())
     }
 }
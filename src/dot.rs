@@ -0,0 +1,153 @@
+//! Graphviz DOT export.
+//!
+//! # Examples
+//!
+//! ```
+//! # use ego_tree::tree;
+//! let tree = tree!('a' => { 'b', 'c' });
+//! assert_eq!(
+//!     tree.to_dot(),
+//!     "digraph {\n    0 [label=\"a\"];\n    1 [label=\"b\"];\n    0 -> 1;\n    2 [label=\"c\"];\n    0 -> 2;\n}\n",
+//! );
+//! ```
+
+use std::fmt::{self, Display, Write};
+
+use crate::{NodeRef, Tree};
+
+/// Options for DOT output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DotOptions {
+    /// Adds a dashed edge from each node to its next sibling.
+    pub sibling_edges: bool,
+    /// Adds each orphan subtree of the tree as a separate cluster.
+    ///
+    /// Only used when writing a whole tree.
+    pub orphans: bool,
+}
+
+impl<T: Display> Tree<T> {
+    /// Returns the tree as a Graphviz digraph, with default options.
+    ///
+    /// Nodes are named after the index of their `NodeId` and labeled with
+    /// their value.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        self.write_dot(&mut dot, &DotOptions::default())
+            .expect("a Display implementation returned an error unexpectedly");
+        dot
+    }
+
+    /// Writes the tree as a Graphviz digraph, labeling nodes with their value.
+    pub fn write_dot<W: Write>(&self, writer: &mut W, options: &DotOptions) -> fmt::Result {
+        self.write_dot_with(writer, options, |node| node.value().to_string())
+    }
+}
+
+impl<T> Tree<T> {
+    /// Writes the tree as a Graphviz digraph, labeling nodes with `label`.
+    pub fn write_dot_with<W, F>(
+        &self,
+        writer: &mut W,
+        options: &DotOptions,
+        mut label: F,
+    ) -> fmt::Result
+    where
+        W: Write,
+        F: FnMut(NodeRef<T>) -> String,
+    {
+        writeln!(writer, "digraph {{")?;
+        write_nodes(writer, self.root(), options, &mut label, "    ")?;
+        if options.orphans {
+            let root = self.root();
+            for orphan in self
+                .nodes()
+                .filter(|node| node.parent().is_none() && *node != root)
+            {
+                writeln!(writer, "    subgraph cluster_{} {{", orphan.id.to_index())?;
+                write_nodes(writer, orphan, options, &mut label, "        ")?;
+                writeln!(writer, "    }}")?;
+            }
+        }
+        writeln!(writer, "}}")
+    }
+}
+
+impl<'a, T: 'a + Display> NodeRef<'a, T> {
+    /// Writes the subtree of this node as a Graphviz digraph, labeling nodes
+    /// with their value.
+    pub fn write_dot<W: Write>(&self, writer: &mut W, options: &DotOptions) -> fmt::Result {
+        self.write_dot_with(writer, options, |node| node.value().to_string())
+    }
+}
+
+impl<'a, T: 'a> NodeRef<'a, T> {
+    /// Writes the subtree of this node as a Graphviz digraph, labeling nodes
+    /// with `label`.
+    pub fn write_dot_with<W, F>(
+        &self,
+        writer: &mut W,
+        options: &DotOptions,
+        mut label: F,
+    ) -> fmt::Result
+    where
+        W: Write,
+        F: FnMut(NodeRef<'a, T>) -> String,
+    {
+        writeln!(writer, "digraph {{")?;
+        write_nodes(writer, *self, options, &mut label, "    ")?;
+        writeln!(writer, "}}")
+    }
+}
+
+/// Writes the nodes and edges of a subtree.
+fn write_nodes<'a, T, W, F>(
+    writer: &mut W,
+    root: NodeRef<'a, T>,
+    options: &DotOptions,
+    label: &mut F,
+    indent: &str,
+) -> fmt::Result
+where
+    W: Write,
+    F: FnMut(NodeRef<'a, T>) -> String,
+{
+    for node in root.descendants() {
+        let id = node.id.to_index();
+        writeln!(writer, "{indent}{id} [label={}];", Quoted(&label(node)))?;
+        if node != root {
+            if let Some(parent) = node.parent() {
+                writeln!(writer, "{indent}{} -> {id};", parent.id.to_index())?;
+            }
+        }
+        if options.sibling_edges && node != root {
+            if let Some(next_sibling) = node.next_sibling() {
+                writeln!(
+                    writer,
+                    "{indent}{id} -> {} [style=dashed, constraint=false];",
+                    next_sibling.id.to_index()
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Quotes a DOT string.
+struct Quoted<'s>(&'s str);
+
+impl<'s> Display for Quoted<'s> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => {}
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
@@ -0,0 +1,133 @@
+//! Indented outlines, one node per line.
+//!
+//! # Examples
+//!
+//! ```
+//! # use ego_tree::tree;
+//! use ego_tree::Tree;
+//!
+//! let tree = tree!("root" => { "a" => { "b" }, "c" });
+//! let outline = tree.to_indented("  ");
+//! assert_eq!(outline, "root\n  a\n    b\n  c\n");
+//!
+//! let parsed = Tree::from_indented_lines(outline.lines(), |line| {
+//!     let value = line.trim_start();
+//!     (line.len() - value.len(), value.to_owned())
+//! })
+//! .unwrap();
+//! assert_eq!(format!("{:#?}", parsed), format!("{:#?}", tree));
+//! ```
+
+use std::fmt::{self, Display, Write};
+
+use crate::display::visit;
+use crate::parse::ParseError;
+use crate::{NodeId, Tree};
+
+impl<T: Display> Tree<T> {
+    /// Returns the tree as indented lines, repeating `indent` once per depth.
+    ///
+    /// This is the inverse of [`Tree::from_indented_lines`].
+    pub fn to_indented(&self, indent: &str) -> String {
+        let mut s = String::new();
+        self.write_indented(&mut s, indent)
+            .expect("a Display implementation returned an error unexpectedly");
+        s
+    }
+
+    /// Writes the tree as indented lines, repeating `indent` once per depth.
+    pub fn write_indented<W: Write>(&self, writer: &mut W, indent: &str) -> fmt::Result {
+        visit(self.root(), |node, depth| {
+            for _ in 0..depth {
+                writer.write_str(indent)?;
+            }
+            writeln!(writer, "{}", node.value())
+        })
+    }
+}
+
+impl<T> Tree<T> {
+    /// Builds a tree from indented lines.
+    ///
+    /// `indent` returns the indentation width and the value of each line.
+    /// The first line is the root. Each following line is a child of the
+    /// closest previous line with a smaller indentation, which must be the
+    /// indentation of a previous sibling if there is one. Blank lines are
+    /// skipped. This is the inverse of [`Tree::to_indented`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are no lines, if a line is not indented
+    /// more than the root, or if a line is indented less than its previous
+    /// line without matching the indentation of an outer line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ego_tree::{tree, Tree};
+    /// let outline = "\
+    /// - root
+    ///   - a
+    ///     - b
+    ///   - c
+    /// ";
+    /// let tree = Tree::from_indented_lines(outline.lines(), |line| {
+    ///     let value = line.trim_start();
+    ///     (line.len() - value.len(), value.trim_start_matches("- ").to_owned())
+    /// })
+    /// .unwrap();
+    /// assert_eq!(tree.to_indented("  "), "root\n  a\n    b\n  c\n");
+    /// ```
+    pub fn from_indented_lines<I, F>(lines: I, mut indent: F) -> Result<Self, ParseError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+        F: FnMut(&str) -> (usize, T),
+    {
+        let mut lines = lines
+            .into_iter()
+            .zip(1..)
+            .filter(|(line, _)| !line.as_ref().trim().is_empty());
+        let (root, _) = lines
+            .next()
+            .ok_or_else(|| ParseError::new(1, "expected a root node"))?;
+        let (root_width, root) = indent(root.as_ref());
+        let mut tree = Tree::new(root);
+
+        // Path from the root to the last node: ID and indentation width.
+        let mut path: Vec<(NodeId, usize)> = vec![(tree.root().id, root_width)];
+
+        for (line, number) in lines {
+            let (width, value) = indent(line.as_ref());
+            if width <= root_width {
+                return Err(ParseError::new(
+                    number,
+                    format!(
+                        "indentation {} is not deeper than the root indentation {}",
+                        width, root_width
+                    ),
+                ));
+            }
+
+            let depth = path.iter().rposition(|&(_, w)| w < width).unwrap();
+            if let Some(&(_, sibling_width)) = path.get(depth + 1) {
+                if sibling_width != width {
+                    return Err(ParseError::new(
+                        number,
+                        format!(
+                            "indentation {} does not match any outer indentation, expected {}",
+                            width, sibling_width
+                        ),
+                    ));
+                }
+            }
+            path.truncate(depth + 1);
+
+            let parent_id = path[depth].0;
+            let id = tree.get_mut(parent_id).unwrap().append(value).id;
+            path.push((id, width));
+        }
+
+        Ok(tree)
+    }
+}
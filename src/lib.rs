@@ -35,12 +35,18 @@
     missing_copy_implementations
 )]
 
+use std::cmp::Ordering;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::num::NonZeroUsize;
 
 /// Vec-backed ID-tree.
 ///
 /// Always contains at least a root node.
+///
+/// `==` and `Hash` compare the underlying storage, including the order in
+/// which nodes were created and orphans, so trees that look the same can
+/// compare unequal. Use [`Tree::structural_eq`] and [`StructuralHash`] to
+/// compare only the subtree of the root, its shape and its values.
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Tree<T> {
     vec: Vec<Node<T>>,
@@ -94,7 +100,9 @@ impl<T> Node<T> {
 }
 
 /// Node reference.
-#[derive(Debug)]
+///
+/// The alternate `Debug` format and the `Display` format of a node render only
+/// its subtree, in the same way as for a `Tree`.
 pub struct NodeRef<'a, T: 'a> {
     /// Node ID.
     id: NodeId,
@@ -117,6 +125,28 @@ pub struct NodeMut<'a, T: 'a> {
 
 // Trait implementations regardless of T.
 
+impl<'a, T: 'a + Debug> Debug for NodeRef<'a, T> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        if f.alternate() {
+            crate::display::fmt_macro(*self, f)
+        } else {
+            f.debug_struct("NodeRef")
+                .field("id", &self.id)
+                .field("tree", &self.tree)
+                .field("node", &self.node)
+                .finish()
+        }
+    }
+}
+
+impl<'a, T: 'a + Display> Display for NodeRef<'a, T> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        use crate::display::DisplayOptions;
+
+        write!(f, "{}", self.display_with(DisplayOptions::default()))
+    }
+}
+
 impl<'a, T: 'a> Copy for NodeRef<'a, T> {}
 impl<'a, T: 'a> Clone for NodeRef<'a, T> {
     fn clone(&self) -> Self {
@@ -125,6 +155,8 @@ impl<'a, T: 'a> Clone for NodeRef<'a, T> {
 }
 
 impl<'a, T: 'a> Eq for NodeRef<'a, T> {}
+/// Compares the identity of nodes: `==` is true for references to the same
+/// node of the same tree. Use [`NodeRef::subtree_eq`] to compare subtrees.
 impl<'a, T: 'a> PartialEq for NodeRef<'a, T> {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
@@ -149,7 +181,7 @@ impl<T> Tree<T> {
     }
 
     /// Returns a reference to the specified node.
-    pub fn get(&self, id: NodeId) -> Option<NodeRef<T>> {
+    pub fn get(&self, id: NodeId) -> Option<NodeRef<'_, T>> {
         self.vec.get(id.to_index()).map(|node| NodeRef {
             id,
             node,
@@ -158,7 +190,7 @@ impl<T> Tree<T> {
     }
 
     /// Returns a mutator of the specified node.
-    pub fn get_mut(&mut self, id: NodeId) -> Option<NodeMut<T>> {
+    pub fn get_mut(&mut self, id: NodeId) -> Option<NodeMut<'_, T>> {
         let exists = self.vec.get(id.to_index()).map(|_| ());
         exists.map(move |_| NodeMut { id, tree: self })
     }
@@ -174,7 +206,7 @@ impl<T> Tree<T> {
     /// Returns a reference to the specified node.
     /// # Safety
     /// The caller must ensure that `id` is a valid node ID.
-    pub unsafe fn get_unchecked(&self, id: NodeId) -> NodeRef<T> {
+    pub unsafe fn get_unchecked(&self, id: NodeId) -> NodeRef<'_, T> {
         NodeRef {
             id,
             node: self.node(id),
@@ -185,22 +217,22 @@ impl<T> Tree<T> {
     /// Returns a mutator of the specified node.
     /// # Safety
     /// The caller must ensure that `id` is a valid node ID.
-    pub unsafe fn get_unchecked_mut(&mut self, id: NodeId) -> NodeMut<T> {
+    pub unsafe fn get_unchecked_mut(&mut self, id: NodeId) -> NodeMut<'_, T> {
         NodeMut { id, tree: self }
     }
 
     /// Returns a reference to the root node.
-    pub fn root(&self) -> NodeRef<T> {
+    pub fn root(&self) -> NodeRef<'_, T> {
         unsafe { self.get_unchecked(NodeId::from_index(0)) }
     }
 
     /// Returns a mutator of the root node.
-    pub fn root_mut(&mut self) -> NodeMut<T> {
+    pub fn root_mut(&mut self) -> NodeMut<'_, T> {
         unsafe { self.get_unchecked_mut(NodeId::from_index(0)) }
     }
 
     /// Creates an orphan node.
-    pub fn orphan(&mut self, value: T) -> NodeMut<T> {
+    pub fn orphan(&mut self, value: T) -> NodeMut<'_, T> {
         let id = unsafe { NodeId::from_index(self.vec.len()) };
         self.vec.push(Node::new(value));
         unsafe { self.get_unchecked_mut(id) }
@@ -209,7 +241,7 @@ impl<T> Tree<T> {
     /// Merge with another tree as orphan, returning the new root of tree being merged.
     // Allowing this for compactness.
     #[allow(clippy::option_map_unit_fn)]
-    pub fn extend_tree(&mut self, mut other_tree: Tree<T>) -> NodeMut<T> {
+    pub fn extend_tree(&mut self, mut other_tree: Tree<T>) -> NodeMut<'_, T> {
         let offset = self.vec.len();
         let offset_id = |id: NodeId| -> NodeId {
             let old_index = id.to_index();
@@ -229,6 +261,88 @@ impl<T> Tree<T> {
         self.vec.extend(other_tree.vec);
         unsafe { self.get_unchecked_mut(other_tree_root_id) }
     }
+
+    /// Swaps the positions of two nodes, along with their subtrees.
+    ///
+    /// Each node takes the parent and siblings of the other. Swapping a node
+    /// with itself does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if one node is an ancestor of the other.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is not valid.
+    pub fn swap_nodes(&mut self, a: NodeId, b: NodeId) -> Result<(), SwapError> {
+        if a == b {
+            return Ok(());
+        }
+        if self.get(b).unwrap().ancestors().any(|node| node.id == a) {
+            return Err(SwapError {
+                ancestor: a,
+                descendant: b,
+            });
+        }
+        if self.get(a).unwrap().ancestors().any(|node| node.id == b) {
+            return Err(SwapError {
+                ancestor: b,
+                descendant: a,
+            });
+        }
+
+        let swap = |id: NodeId| match id {
+            id if id == a => b,
+            id if id == b => a,
+            id => id,
+        };
+
+        let (a_parent_id, a_prev_sibling_id, a_next_sibling_id) = {
+            let node = unsafe { self.node(a) };
+            (node.parent, node.prev_sibling, node.next_sibling)
+        };
+        let (b_parent_id, b_prev_sibling_id, b_next_sibling_id) = {
+            let node = unsafe { self.node(b) };
+            (node.parent, node.prev_sibling, node.next_sibling)
+        };
+
+        for (id, parent_id, prev_sibling_id, next_sibling_id) in [
+            (a, b_parent_id, b_prev_sibling_id, b_next_sibling_id),
+            (b, a_parent_id, a_prev_sibling_id, a_next_sibling_id),
+        ] {
+            let prev_sibling_id = prev_sibling_id.map(swap);
+            let next_sibling_id = next_sibling_id.map(swap);
+            {
+                let node = unsafe { self.node_mut(id) };
+                node.parent = parent_id;
+                node.prev_sibling = prev_sibling_id;
+                node.next_sibling = next_sibling_id;
+            }
+
+            if let Some(sibling_id) = prev_sibling_id.filter(|&s| s != a && s != b) {
+                unsafe {
+                    self.node_mut(sibling_id).next_sibling = Some(id);
+                }
+            }
+            if let Some(sibling_id) = next_sibling_id.filter(|&s| s != a && s != b) {
+                unsafe {
+                    self.node_mut(sibling_id).prev_sibling = Some(id);
+                }
+            }
+        }
+
+        for parent_id in [a_parent_id, b_parent_id.filter(|&p| Some(p) != a_parent_id)]
+            .into_iter()
+            .flatten()
+        {
+            let parent = unsafe { self.node_mut(parent_id) };
+            parent.children = parent
+                .children
+                .map(|(first_child_id, last_child_id)| (swap(first_child_id), swap(last_child_id)));
+        }
+
+        Ok(())
+    }
 }
 
 impl<'a, T: 'a> NodeRef<'a, T> {
@@ -284,7 +398,7 @@ impl<'a, T: 'a> NodeRef<'a, T> {
 
     /// Returns the index of the given child or None if child doesn't exist.
     /// This function may take up to linear time in worst case scenarios.
-    pub fn index_of_child(&self, child: &NodeRef<T>) -> Option<usize> {
+    pub fn index_of_child(&self, child: &NodeRef<'_, T>) -> Option<usize> {
         self.children()
             .enumerate()
             .find(|(_, each)| each == child)
@@ -323,31 +437,31 @@ impl<'a, T: 'a> NodeMut<'a, T> {
     }
 
     /// Returns the parent of this node.
-    pub fn parent(&mut self) -> Option<NodeMut<T>> {
+    pub fn parent(&mut self) -> Option<NodeMut<'_, T>> {
         let id = self.node().parent;
         id.map(move |id| unsafe { self.tree.get_unchecked_mut(id) })
     }
 
     /// Returns the previous sibling of this node.
-    pub fn prev_sibling(&mut self) -> Option<NodeMut<T>> {
+    pub fn prev_sibling(&mut self) -> Option<NodeMut<'_, T>> {
         let id = self.node().prev_sibling;
         id.map(move |id| unsafe { self.tree.get_unchecked_mut(id) })
     }
 
     /// Returns the next sibling of this node.
-    pub fn next_sibling(&mut self) -> Option<NodeMut<T>> {
+    pub fn next_sibling(&mut self) -> Option<NodeMut<'_, T>> {
         let id = self.node().next_sibling;
         id.map(move |id| unsafe { self.tree.get_unchecked_mut(id) })
     }
 
     /// Returns the first child of this node.
-    pub fn first_child(&mut self) -> Option<NodeMut<T>> {
+    pub fn first_child(&mut self) -> Option<NodeMut<'_, T>> {
         let ids = self.node().children;
         ids.map(move |(id, _)| unsafe { self.tree.get_unchecked_mut(id) })
     }
 
     /// Returns the last child of this node.
-    pub fn last_child(&mut self) -> Option<NodeMut<T>> {
+    pub fn last_child(&mut self) -> Option<NodeMut<'_, T>> {
         let ids = self.node().children;
         ids.map(move |(_, id)| unsafe { self.tree.get_unchecked_mut(id) })
     }
@@ -363,13 +477,13 @@ impl<'a, T: 'a> NodeMut<'a, T> {
     }
 
     /// Appends a new child to this node.
-    pub fn append(&mut self, value: T) -> NodeMut<T> {
+    pub fn append(&mut self, value: T) -> NodeMut<'_, T> {
         let id = self.tree.orphan(value).id;
         self.append_id(id)
     }
 
     /// Prepends a new child to this node.
-    pub fn prepend(&mut self, value: T) -> NodeMut<T> {
+    pub fn prepend(&mut self, value: T) -> NodeMut<'_, T> {
         let id = self.tree.orphan(value).id;
         self.prepend_id(id)
     }
@@ -380,19 +494,19 @@ impl<'a, T: 'a> NodeMut<'a, T> {
     /// # Panics
     ///
     /// Panics if `index` is not valid.
-    pub fn insert(&mut self, value: T, index: usize) -> NodeMut<T> {
+    pub fn insert(&mut self, value: T, index: usize) -> NodeMut<'_, T> {
         let id = self.tree.orphan(value).id;
         self.insert_id(id, index)
     }
 
     /// Appends a subtree, return the root of the merged subtree.
-    pub fn append_subtree(&mut self, subtree: Tree<T>) -> NodeMut<T> {
+    pub fn append_subtree(&mut self, subtree: Tree<T>) -> NodeMut<'_, T> {
         let root_id = self.tree.extend_tree(subtree).id;
         self.append_id(root_id)
     }
 
     /// Prepends a subtree, return the root of the merged subtree.
-    pub fn prepend_subtree(&mut self, subtree: Tree<T>) -> NodeMut<T> {
+    pub fn prepend_subtree(&mut self, subtree: Tree<T>) -> NodeMut<'_, T> {
         let root_id = self.tree.extend_tree(subtree).id;
         self.prepend_id(root_id)
     }
@@ -402,7 +516,7 @@ impl<'a, T: 'a> NodeMut<'a, T> {
     /// # Panics
     ///
     /// Panics if this node is an orphan.
-    pub fn insert_before(&mut self, value: T) -> NodeMut<T> {
+    pub fn insert_before(&mut self, value: T) -> NodeMut<'_, T> {
         let id = self.tree.orphan(value).id;
         self.insert_id_before(id)
     }
@@ -412,7 +526,7 @@ impl<'a, T: 'a> NodeMut<'a, T> {
     /// # Panics
     ///
     /// Panics if this node is an orphan.
-    pub fn insert_after(&mut self, value: T) -> NodeMut<T> {
+    pub fn insert_after(&mut self, value: T) -> NodeMut<'_, T> {
         let id = self.tree.orphan(value).id;
         self.insert_id_after(id)
     }
@@ -459,7 +573,7 @@ impl<'a, T: 'a> NodeMut<'a, T> {
     /// # Panics
     ///
     /// Panics if `new_child_id` is not valid.
-    pub fn append_id(&mut self, new_child_id: NodeId) -> NodeMut<T> {
+    pub fn append_id(&mut self, new_child_id: NodeId) -> NodeMut<'_, T> {
         let last_child_id = self.node().children.map(|(_, id)| id);
         {
             let mut new_child = self.tree.get_mut(new_child_id).unwrap();
@@ -491,7 +605,7 @@ impl<'a, T: 'a> NodeMut<'a, T> {
     /// # Panics
     ///
     /// Panics if `new_child_id` or `index` are not valid.
-    pub fn insert_id(&mut self, new_child_id: NodeId, index: usize) -> NodeMut<T> {
+    pub fn insert_id(&mut self, new_child_id: NodeId, index: usize) -> NodeMut<'_, T> {
         if index == 0 {
             return self.prepend_id(new_child_id);
         }
@@ -515,7 +629,7 @@ impl<'a, T: 'a> NodeMut<'a, T> {
     /// # Panics
     ///
     /// Panics if `new_child_id` is not valid.
-    pub fn prepend_id(&mut self, new_child_id: NodeId) -> NodeMut<T> {
+    pub fn prepend_id(&mut self, new_child_id: NodeId) -> NodeMut<'_, T> {
         let first_child_id = self.node().children.map(|(id, _)| id);
         {
             let mut new_child = self.tree.get_mut(new_child_id).unwrap();
@@ -547,7 +661,7 @@ impl<'a, T: 'a> NodeMut<'a, T> {
     ///
     /// - Panics if `new_sibling_id` is not valid.
     /// - Panics if this node is an orphan.
-    pub fn insert_id_before(&mut self, new_sibling_id: NodeId) -> NodeMut<T> {
+    pub fn insert_id_before(&mut self, new_sibling_id: NodeId) -> NodeMut<'_, T> {
         let parent_id = self.node().parent.unwrap();
         let prev_sibling_id = self.node().prev_sibling;
 
@@ -584,7 +698,7 @@ impl<'a, T: 'a> NodeMut<'a, T> {
     ///
     /// - Panics if `new_sibling_id` is not valid.
     /// - Panics if this node is an orphan.
-    pub fn insert_id_after(&mut self, new_sibling_id: NodeId) -> NodeMut<T> {
+    pub fn insert_id_after(&mut self, new_sibling_id: NodeId) -> NodeMut<'_, T> {
         let parent_id = self.node().parent.unwrap();
         let next_sibling_id = self.node().next_sibling;
 
@@ -680,6 +794,457 @@ impl<'a, T: 'a> NodeMut<'a, T> {
 
         self.node().children = Some((new_child_ids.0, old_child_ids.1));
     }
+
+    /// Appends a range of siblings to this node, from `first_id` to `last_id`
+    /// inclusive.
+    ///
+    /// Siblings are unlinked and relinked as a whole. This function takes
+    /// linear time in the length of the range, to update parents.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `first_id` or `last_id` are not valid.
+    /// - Panics if `last_id` is neither `first_id` nor one of its next siblings.
+    /// - Panics if this node or one of its ancestors is in the range.
+    pub fn append_sibling_range(&mut self, first_id: NodeId, last_id: NodeId) {
+        self.check_range(first_id, last_id);
+        self.detach_range(first_id, last_id);
+        let last_child_id = self.node().children.map(|(_, id)| id);
+        self.attach_range(first_id, last_id, last_child_id, None);
+    }
+
+    /// Prepends a range of siblings to this node, from `first_id` to `last_id`
+    /// inclusive.
+    ///
+    /// Siblings are unlinked and relinked as a whole. This function takes
+    /// linear time in the length of the range, to update parents.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `first_id` or `last_id` are not valid.
+    /// - Panics if `last_id` is neither `first_id` nor one of its next siblings.
+    /// - Panics if this node or one of its ancestors is in the range.
+    pub fn prepend_sibling_range(&mut self, first_id: NodeId, last_id: NodeId) {
+        self.check_range(first_id, last_id);
+        self.detach_range(first_id, last_id);
+        let first_child_id = self.node().children.map(|(id, _)| id);
+        self.attach_range(first_id, last_id, None, first_child_id);
+    }
+
+    /// Inserts a range of siblings into this node at given index, from
+    /// `first_id` to `last_id` inclusive.
+    ///
+    /// The index is taken among the children left once the range is removed.
+    /// This function may take up to linear time in worst case scenarios.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `first_id`, `last_id` or `index` are not valid.
+    /// - Panics if `last_id` is neither `first_id` nor one of its next siblings.
+    /// - Panics if this node or one of its ancestors is in the range.
+    pub fn insert_sibling_range(&mut self, first_id: NodeId, last_id: NodeId, index: usize) {
+        self.check_range(first_id, last_id);
+        let prev_sibling_id = if index == 0 {
+            None
+        } else {
+            // Skips the range while counting, so that the index is resolved
+            // before anything is unlinked.
+            let mut in_range = false;
+            let prev_sibling = unsafe { self.tree.get_unchecked(self.id) }
+                .children()
+                .filter(|child| {
+                    let skip = in_range || child.id == first_id;
+                    in_range = skip && child.id != last_id;
+                    !skip
+                })
+                .nth(index - 1) // worst case O(n)
+                .unwrap_or_else(|| panic!("No child found at index {}", index - 1));
+            Some(prev_sibling.id)
+        };
+
+        self.detach_range(first_id, last_id);
+        let next_sibling_id = match prev_sibling_id {
+            Some(id) => unsafe { self.tree.node(id).next_sibling },
+            None => self.node().children.map(|(id, _)| id),
+        };
+        self.attach_range(first_id, last_id, prev_sibling_id, next_sibling_id);
+    }
+
+    /// Checks that `first_id` to `last_id` is a range of siblings that does
+    /// not contain this node or any of its ancestors.
+    fn check_range(&self, first_id: NodeId, last_id: NodeId) {
+        let mut id = first_id;
+        let mut ids = Vec::new();
+        loop {
+            let node = self.tree.get(id).unwrap();
+            ids.push(id);
+            if id == last_id {
+                break;
+            }
+            id = node
+                .node
+                .next_sibling
+                .unwrap_or_else(|| panic!("{:?} is not a next sibling of {:?}", last_id, first_id));
+        }
+
+        // Only the ancestor that is a sibling of the range can be in it.
+        let parent_id = unsafe { self.tree.node(first_id).parent };
+        let mut id = Some(self.id);
+        while let Some(ancestor_id) = id {
+            let ancestor = unsafe { self.tree.node(ancestor_id) };
+            if ancestor.parent == parent_id {
+                assert!(
+                    !ids.contains(&ancestor_id),
+                    "Cannot move a range containing its new parent or one of its ancestors"
+                );
+                break;
+            }
+            id = ancestor.parent;
+        }
+    }
+
+    /// Unlinks a checked range of siblings from its parent, keeping the links
+    /// within the range.
+    fn detach_range(&mut self, first_id: NodeId, last_id: NodeId) {
+        let parent_id = unsafe { self.tree.node(first_id).parent };
+        let prev_sibling_id = unsafe { self.tree.node(first_id).prev_sibling };
+        let next_sibling_id = unsafe { self.tree.node(last_id).next_sibling };
+
+        unsafe {
+            self.tree.node_mut(first_id).prev_sibling = None;
+            self.tree.node_mut(last_id).next_sibling = None;
+        }
+
+        if let Some(id) = prev_sibling_id {
+            unsafe {
+                self.tree.node_mut(id).next_sibling = next_sibling_id;
+            }
+        }
+        if let Some(id) = next_sibling_id {
+            unsafe {
+                self.tree.node_mut(id).prev_sibling = prev_sibling_id;
+            }
+        }
+
+        if let Some(parent_id) = parent_id {
+            let parent = unsafe { self.tree.node_mut(parent_id) };
+            let (first_child_id, last_child_id) = parent.children.unwrap();
+            parent.children = match (prev_sibling_id, next_sibling_id) {
+                (None, None) => None,
+                (None, Some(next_sibling_id)) => Some((next_sibling_id, last_child_id)),
+                (Some(prev_sibling_id), None) => Some((first_child_id, prev_sibling_id)),
+                (Some(_), Some(_)) => Some((first_child_id, last_child_id)),
+            };
+        }
+    }
+
+    /// Links a detached range of siblings into this node, between the given
+    /// children.
+    fn attach_range(
+        &mut self,
+        first_id: NodeId,
+        last_id: NodeId,
+        prev_sibling_id: Option<NodeId>,
+        next_sibling_id: Option<NodeId>,
+    ) {
+        let mut id = Some(first_id);
+        while let Some(child_id) = id {
+            let child = unsafe { self.tree.node_mut(child_id) };
+            child.parent = Some(self.id);
+            id = child.next_sibling;
+        }
+
+        unsafe {
+            self.tree.node_mut(first_id).prev_sibling = prev_sibling_id;
+            self.tree.node_mut(last_id).next_sibling = next_sibling_id;
+        }
+
+        if let Some(id) = prev_sibling_id {
+            unsafe {
+                self.tree.node_mut(id).next_sibling = Some(first_id);
+            }
+        }
+        if let Some(id) = next_sibling_id {
+            unsafe {
+                self.tree.node_mut(id).prev_sibling = Some(last_id);
+            }
+        }
+
+        let (first_child_id, last_child_id) = self.node().children.unwrap_or((first_id, last_id));
+        self.node().children = Some((
+            if prev_sibling_id.is_none() {
+                first_id
+            } else {
+                first_child_id
+            },
+            if next_sibling_id.is_none() {
+                last_id
+            } else {
+                last_child_id
+            },
+        ));
+    }
+
+    /// Replaces this node with its children in its parent's children,
+    /// leaving this node detached and childless.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this node is an orphan.
+    pub fn unwrap(&mut self) {
+        let parent_id = self.node().parent.unwrap();
+        let (first_child_id, last_child_id) = match self.node().children.take() {
+            Some(ids) => ids,
+            None => return self.detach(),
+        };
+        let prev_sibling_id = self.node().prev_sibling;
+        let next_sibling_id = self.node().next_sibling;
+
+        {
+            self.node().parent = None;
+            self.node().prev_sibling = None;
+            self.node().next_sibling = None;
+        }
+
+        let mut child_id = Some(first_child_id);
+        while let Some(id) = child_id {
+            let child = unsafe { self.tree.node_mut(id) };
+            child.parent = Some(parent_id);
+            child_id = child.next_sibling;
+        }
+
+        unsafe {
+            self.tree.node_mut(first_child_id).prev_sibling = prev_sibling_id;
+            self.tree.node_mut(last_child_id).next_sibling = next_sibling_id;
+        }
+
+        if let Some(id) = prev_sibling_id {
+            unsafe {
+                self.tree.node_mut(id).next_sibling = Some(first_child_id);
+            }
+        }
+        if let Some(id) = next_sibling_id {
+            unsafe {
+                self.tree.node_mut(id).prev_sibling = Some(last_child_id);
+            }
+        }
+
+        let parent = unsafe { self.tree.node_mut(parent_id) };
+        let (parent_first_child_id, parent_last_child_id) = parent.children.unwrap();
+        parent.children = Some((
+            if parent_first_child_id == self.id {
+                first_child_id
+            } else {
+                parent_first_child_id
+            },
+            if parent_last_child_id == self.id {
+                last_child_id
+            } else {
+                parent_last_child_id
+            },
+        ));
+    }
+
+    /// Inserts a new node in place of this node, with this node as its only
+    /// child, returning the new node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this node is an orphan.
+    pub fn wrap(&mut self, value: T) -> NodeMut<'_, T> {
+        let parent_id = self.node().parent.unwrap();
+        let prev_sibling_id = self.node().prev_sibling;
+        let next_sibling_id = self.node().next_sibling;
+        let self_id = self.id;
+
+        let wrapper_id = {
+            let mut wrapper = self.tree.orphan(value);
+            wrapper.node().parent = Some(parent_id);
+            wrapper.node().prev_sibling = prev_sibling_id;
+            wrapper.node().next_sibling = next_sibling_id;
+            wrapper.node().children = Some((self_id, self_id));
+            wrapper.id
+        };
+
+        {
+            self.node().parent = Some(wrapper_id);
+            self.node().prev_sibling = None;
+            self.node().next_sibling = None;
+        }
+
+        if let Some(id) = prev_sibling_id {
+            unsafe {
+                self.tree.node_mut(id).next_sibling = Some(wrapper_id);
+            }
+        }
+        if let Some(id) = next_sibling_id {
+            unsafe {
+                self.tree.node_mut(id).prev_sibling = Some(wrapper_id);
+            }
+        }
+
+        {
+            let parent = unsafe { self.tree.node_mut(parent_id) };
+            let (first_child_id, last_child_id) = parent.children.unwrap();
+            if first_child_id == self_id {
+                parent.children = Some((wrapper_id, last_child_id));
+            }
+            if last_child_id == self_id {
+                let (first_child_id, _) = parent.children.unwrap();
+                parent.children = Some((first_child_id, wrapper_id));
+            }
+        }
+
+        unsafe { self.tree.get_unchecked_mut(wrapper_id) }
+    }
+
+    /// Reverses the order of the children of this node.
+    pub fn reverse_children(&mut self) {
+        let (first_child_id, last_child_id) = match self.node().children {
+            Some(ids) => ids,
+            None => return,
+        };
+
+        let mut id = Some(first_child_id);
+        while let Some(child_id) = id {
+            let child = unsafe { self.tree.node_mut(child_id) };
+            std::mem::swap(&mut child.prev_sibling, &mut child.next_sibling);
+            id = child.prev_sibling;
+        }
+
+        self.node().children = Some((last_child_id, first_child_id));
+    }
+
+    /// Reverses the order of the children of this node and of all its
+    /// descendants.
+    pub fn reverse_descendants(&mut self) {
+        let mut next = Some(self.id);
+        while let Some(id) = next {
+            unsafe { self.tree.get_unchecked_mut(id).reverse_children() };
+            next = match unsafe { self.tree.node(id).children } {
+                Some((first_child_id, _)) => Some(first_child_id),
+                None => self.following_id(id),
+            };
+        }
+    }
+
+    /// Sorts the children of this node with a comparator function.
+    ///
+    /// The sort is stable.
+    pub fn sort_children_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(NodeRef<T>, NodeRef<T>) -> Ordering,
+    {
+        let mut ids = Vec::new();
+        let mut next = self.node().children.map(|(id, _)| id);
+        while let Some(id) = next {
+            ids.push(id);
+            next = unsafe { self.tree.node(id).next_sibling };
+        }
+        if ids.len() < 2 {
+            return;
+        }
+
+        let tree = &*self.tree;
+        ids.sort_by(|&a, &b| unsafe { compare(tree.get_unchecked(a), tree.get_unchecked(b)) });
+
+        for (i, &id) in ids.iter().enumerate() {
+            let child = unsafe { self.tree.node_mut(id) };
+            child.prev_sibling = i.checked_sub(1).map(|i| ids[i]);
+            child.next_sibling = ids.get(i + 1).copied();
+        }
+        self.node().children = Some((ids[0], ids[ids.len() - 1]));
+    }
+
+    /// Sorts the children of this node with a key extraction function.
+    ///
+    /// The sort is stable.
+    pub fn sort_children_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(NodeRef<T>) -> K,
+    {
+        self.sort_children_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Retains only the descendants of this node for which the predicate
+    /// returns `true`.
+    ///
+    /// Descendants are visited in tree order. Rejected nodes are either
+    /// detached along with their subtree or unwrapped, depending on `mode`.
+    /// This node itself is always retained.
+    pub fn retain_descendants<F>(&mut self, mode: RetainMode, mut f: F)
+    where
+        F: FnMut(NodeRef<T>) -> bool,
+    {
+        let mut next = self.node().children.map(|(id, _)| id);
+
+        while let Some(id) = next {
+            if f(unsafe { self.tree.get_unchecked(id) }) {
+                next = match unsafe { self.tree.node(id).children } {
+                    Some((first_child_id, _)) => Some(first_child_id),
+                    None => self.following_id(id),
+                };
+                continue;
+            }
+
+            match mode {
+                RetainMode::DropSubtree => {
+                    next = self.following_id(id);
+                    unsafe { self.tree.get_unchecked_mut(id).detach() };
+                }
+                RetainMode::Unwrap => {
+                    let first_child_id = unsafe { self.tree.node(id).children.map(|(id, _)| id) };
+                    next = first_child_id.or_else(|| self.following_id(id));
+                    unsafe { self.tree.get_unchecked_mut(id).unwrap() };
+                }
+            }
+        }
+    }
+
+    /// Returns the node following the subtree of `id` in tree order, without
+    /// leaving the subtree of this node.
+    fn following_id(&self, mut id: NodeId) -> Option<NodeId> {
+        while id != self.id {
+            let node = unsafe { self.tree.node(id) };
+            if let Some(next_sibling_id) = node.next_sibling {
+                return Some(next_sibling_id);
+            }
+            id = node.parent.unwrap();
+        }
+        None
+    }
+}
+
+/// Error returned by [`Tree::swap_nodes`] when one node is an ancestor of the
+/// other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SwapError {
+    /// The ancestor node.
+    pub ancestor: NodeId,
+    /// The descendant node.
+    pub descendant: NodeId,
+}
+
+impl Display for SwapError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "cannot swap {:?} with its descendant {:?}",
+            self.ancestor, self.descendant
+        )
+    }
+}
+
+impl std::error::Error for SwapError {}
+
+/// Mode of [`NodeMut::retain_descendants`] for rejected nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetainMode {
+    /// Detaches the rejected node along with its descendants.
+    DropSubtree,
+    /// Detaches the rejected node alone, moving its children into its place.
+    Unwrap,
 }
 
 impl<'a, T: 'a> From<NodeMut<'a, T>> for NodeRef<'a, T> {
@@ -691,6 +1256,39 @@ impl<'a, T: 'a> From<NodeMut<'a, T>> for NodeRef<'a, T> {
 /// Iterators.
 pub mod iter;
 
+pub mod algo;
+
+#[cfg(feature = "serde")]
+pub mod serde;
+
+pub mod dot;
+
+pub mod diagram;
+
+pub mod display;
+
+pub mod diff;
+pub use crate::diff::{diff, diff_by_key};
+
+pub mod merge;
+pub use crate::merge::merge3;
+
+pub mod parse;
+
+pub mod indented;
+
+pub mod newick;
+
+pub mod parent_pointer;
+
+pub mod sexpr;
+
+mod node_map;
+pub use crate::node_map::NodeMap;
+
+mod structural;
+pub use crate::structural::StructuralHash;
+
 /// Creates a tree from expressions.
 ///
 /// # Examples
@@ -769,64 +1367,18 @@ macro_rules! tree {
 
 impl<T: Debug> Debug for Tree<T> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        use crate::iter::Edge;
         if f.alternate() {
-            write!(f, "Tree {{")?;
-            for edge in self.root().traverse() {
-                match edge {
-                    Edge::Open(node) if node.has_children() => {
-                        write!(f, " {:?} => {{", node.value())?;
-                    }
-                    Edge::Open(node) if node.next_sibling().is_some() => {
-                        write!(f, " {:?},", node.value())?;
-                    }
-                    Edge::Open(node) => {
-                        write!(f, " {:?}", node.value())?;
-                    }
-                    Edge::Close(node) if node.has_children() => {
-                        if node.next_sibling().is_some() {
-                            write!(f, " }},")?;
-                        } else {
-                            write!(f, " }}")?;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            write!(f, " }}")
+            crate::display::fmt_macro(self.root(), f)
         } else {
             f.debug_struct("Tree").field("vec", &self.vec).finish()
         }
     }
 }
 
-// Handles display
-mod display;
-
 impl<T: Display> Display for Tree<T> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
-        use crate::display::Indentation;
-        use crate::iter::Edge;
+        use crate::display::DisplayOptions;
 
-        let mut indent: Indentation = Indentation::new(true);
-
-        for edge in self.root().traverse() {
-            match edge {
-                Edge::Open(node) if node.has_children() => {
-                    indent.indent(node.next_sibling().is_some());
-                    writeln!(f, "{indent}{}", node.value())?;
-                }
-                Edge::Open(node) => {
-                    indent.indent(node.next_sibling().is_some());
-                    writeln!(f, "{indent}{}", node.value())?;
-                    indent.deindent();
-                }
-                Edge::Close(node) if node.has_children() => {
-                    indent.deindent();
-                }
-                _ => {}
-            }
-        }
-        Ok(())
+        write!(f, "{}", self.display_with(DisplayOptions::default()))
     }
 }
@@ -0,0 +1,404 @@
+//! Three-way merges of trees.
+//!
+//! Nodes of the merged trees are matched by key, as with
+//! [`diff_by_key`](crate::diff_by_key): the roots always match, and other
+//! nodes with the same key match in tree order. Each node of the result takes
+//! the value and parent that either side changed from the base, and siblings
+//! keep the order that either side changed.
+//!
+//! # Examples
+//!
+//! ```
+//! # use ego_tree::tree;
+//! let base = tree!("root" => { "a", "b" => { "c" } });
+//! let ours = tree!("root" => { "b" => { "c", "d" }, "a" });
+//! let theirs = tree!("root" => { "a", "b", "e" });
+//! let merged = ego_tree::merge3(&base, &ours, &theirs, |node| *node.value()).unwrap();
+//! assert_eq!(
+//!     format!("{:#?}", merged),
+//!     format!("{:#?}", tree!("root" => { "b" => { "d" }, "a", "e" })),
+//! );
+//! ```
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+use std::iter;
+
+use crate::diff::pair_by_key;
+use crate::{NodeId, NodeRef, Tree};
+
+const BASE: usize = 0;
+const OURS: usize = 1;
+const THEIRS: usize = 2;
+
+/// Kind of a [`Conflict`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConflictKind {
+    /// Both sides changed the value of a node differently.
+    Value,
+    /// Both sides moved a node to different parents.
+    Move,
+    /// One side deleted a node that the other side changed, or added or
+    /// moved nodes under.
+    Delete,
+    /// Both sides reordered the children of a node differently.
+    Order,
+    /// Moves on both sides make a node its own ancestor.
+    Cycle,
+}
+
+/// Conflict between the changes of both sides of a merge.
+///
+/// A conflict refers to a node by its ID in each tree that has it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    /// Kind of the conflict.
+    pub kind: ConflictKind,
+    /// ID of the node in the base.
+    pub base: Option<NodeId>,
+    /// ID of the node in our tree.
+    pub ours: Option<NodeId>,
+    /// ID of the node in their tree.
+    pub theirs: Option<NodeId>,
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.kind {
+            ConflictKind::Value => write!(f, "conflicting values")?,
+            ConflictKind::Move => write!(f, "conflicting parents")?,
+            ConflictKind::Delete => write!(f, "deleted on one side and changed on the other")?,
+            ConflictKind::Order => write!(f, "conflicting orders of children")?,
+            ConflictKind::Cycle => write!(f, "moves make a node its own ancestor")?,
+        }
+        let ids = [
+            ("base", self.base),
+            ("ours", self.ours),
+            ("theirs", self.theirs),
+        ];
+        let mut ids = ids.iter().filter_map(|&(side, id)| id.map(|id| (side, id)));
+        if let Some((side, id)) = ids.next() {
+            write!(f, " ({} {:?}", side, id)?;
+            for (side, id) in ids {
+                write!(f, ", {} {:?}", side, id)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+/// Error returned by [`merge3`] when the changes of both sides conflict.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Conflicts(pub Vec<Conflict>);
+
+impl Display for Conflicts {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (i, conflict) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", conflict)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for Conflicts {}
+
+/// Merges the changes from `base` to `ours` and from `base` to `theirs`,
+/// matching nodes with equal keys.
+///
+/// Inserts, deletes, moves and value updates merge when only one side makes
+/// them, or when both sides make the same change. Nodes inserted on both
+/// sides with the same key are the same node. Deleting a node deletes its
+/// subtree. Siblings take the order of the side that reordered them, with the
+/// nodes only the other side put there after their previous siblings on that
+/// side.
+///
+/// The merged tree has its nodes in tree order and no orphans.
+///
+/// # Errors
+///
+/// Returns all the conflicts if there are any.
+pub fn merge3<T, K, F>(
+    base: &Tree<T>,
+    ours: &Tree<T>,
+    theirs: &Tree<T>,
+    mut key: F,
+) -> Result<Tree<T>, Conflicts>
+where
+    T: PartialEq + Clone,
+    K: Hash + Eq,
+    F: FnMut(NodeRef<T>) -> K,
+{
+    let merge = Merge::new([base, ours, theirs], &mut key);
+    let len = merge.ids.len();
+    let mut conflicts = Vec::new();
+
+    // Merges the value and the parent of each node, or leaves the value as
+    // `None` if the node is deleted.
+    let mut values = vec![None; len];
+    let mut parents = vec![None; len];
+    let mut deleted_conflicts = vec![false; len];
+    for node in 0..len {
+        let [base_node, ours_node, theirs_node] =
+            [BASE, OURS, THEIRS].map(|side| merge.get(node, side));
+        match (ours_node, theirs_node) {
+            (Some(ours_node), Some(theirs_node)) => {
+                let base_value = base_node.map(|base_node| base_node.value());
+                let value = merge_change(base_value, ours_node.value(), theirs_node.value());
+                if value.is_none() {
+                    conflicts.push(merge.conflict(ConflictKind::Value, node));
+                }
+                values[node] = Some(value.unwrap_or(ours_node.value()));
+
+                let base_parent = merge.parent(node, BASE);
+                let ours_parent = merge.parent(node, OURS).unwrap();
+                let theirs_parent = merge.parent(node, THEIRS).unwrap();
+                let parent = merge_change(base_parent, ours_parent, theirs_parent);
+                if parent.is_none() {
+                    conflicts.push(merge.conflict(ConflictKind::Move, node));
+                }
+                parents[node] = parent.unwrap_or(ours_parent);
+            }
+            (Some(kept), None) | (None, Some(kept)) => {
+                let side = if ours_node.is_some() { OURS } else { THEIRS };
+                match base_node {
+                    Some(base_node) => {
+                        // Deleted on the other side.
+                        if base_node.value() != kept.value()
+                            || merge.parent(node, BASE) != merge.parent(node, side)
+                        {
+                            conflicts.push(merge.conflict(ConflictKind::Delete, node));
+                            deleted_conflicts[node] = true;
+                        }
+                    }
+                    None => {
+                        values[node] = Some(kept.value());
+                        parents[node] = merge.parent(node, side).unwrap();
+                    }
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    // Nodes added or moved under deleted nodes.
+    for node in 0..len {
+        if let (Some(_), Some(parent)) = (values[node], parents[node]) {
+            if values[parent].is_none() && !deleted_conflicts[parent] {
+                conflicts.push(merge.conflict(ConflictKind::Delete, parent));
+                deleted_conflicts[parent] = true;
+            }
+        }
+    }
+
+    // Follows parents from each node, marking nodes on the current path with
+    // 1 and nodes known not to be on a cycle with 2.
+    let mut states = vec![0u8; len];
+    for start in 0..len {
+        let mut path = Vec::new();
+        let mut next = Some(start).filter(|&node| values[node].is_some());
+        while let Some(node) = next {
+            match states[node] {
+                0 => {
+                    states[node] = 1;
+                    path.push(node);
+                    next = parents[node].filter(|&parent| values[parent].is_some());
+                }
+                1 => {
+                    conflicts.push(merge.conflict(ConflictKind::Cycle, node));
+                    break;
+                }
+                _ => break,
+            }
+        }
+        for node in path {
+            states[node] = 2;
+        }
+    }
+    if !conflicts.is_empty() {
+        return Err(Conflicts(conflicts));
+    }
+
+    let mut children = vec![Vec::new(); len];
+    for node in 0..len {
+        if values[node].is_some() {
+            match merge.order(node, &parents) {
+                Some(order) => children[node] = order,
+                None => conflicts.push(merge.conflict(ConflictKind::Order, node)),
+            }
+        }
+    }
+    if !conflicts.is_empty() {
+        return Err(Conflicts(conflicts));
+    }
+
+    let len = values.iter().flatten().count();
+    let mut tree = Tree::with_capacity(values[0].unwrap().clone(), len);
+    let mut stack: Vec<_> = children[0]
+        .iter()
+        .rev()
+        .map(|&child| (child, tree.root().id))
+        .collect();
+    while let Some((node, parent_id)) = stack.pop() {
+        let value = values[node].unwrap().clone();
+        let id = tree.get_mut(parent_id).unwrap().append(value).id();
+        stack.extend(children[node].iter().rev().map(|&child| (child, id)));
+    }
+    Ok(tree)
+}
+
+/// Returns the merged change of a property, or `None` if both sides changed
+/// it differently.
+fn merge_change<V: PartialEq>(base: Option<V>, ours: V, theirs: V) -> Option<V> {
+    if ours == theirs || base.as_ref() == Some(&theirs) {
+        Some(ours)
+    } else if base.as_ref() == Some(&ours) {
+        Some(theirs)
+    } else {
+        None
+    }
+}
+
+/// Nodes of a merge, numbered with the nodes of the base first, in tree
+/// order, so that the root is 0.
+struct Merge<'a, T> {
+    trees: [&'a Tree<T>; 3],
+    /// ID of each node in each tree that has it.
+    ids: Vec<[Option<NodeId>; 3]>,
+    /// Node of each node of each tree, by index.
+    nodes: [Vec<Option<usize>>; 3],
+}
+
+impl<'a, T> Merge<'a, T> {
+    fn new<K, F>(trees: [&'a Tree<T>; 3], key: &mut F) -> Self
+    where
+        K: Hash + Eq,
+        F: FnMut(NodeRef<T>) -> K,
+    {
+        let mut merge = Merge {
+            trees,
+            ids: Vec::new(),
+            nodes: trees.map(|tree| vec![None; tree.vec.len()]),
+        };
+        for node in trees[BASE].root().descendants() {
+            merge.add([Some(node.id), None, None]);
+        }
+
+        let base_root = trees[BASE].root().id;
+        for side in [OURS, THEIRS] {
+            let root = trees[side].root().id;
+            let base_nodes = trees[BASE].root().descendants().skip(1);
+            let nodes = trees[side].root().descendants().skip(1);
+            let pairs = pair_by_key(base_nodes, nodes, &mut *key);
+            for (base_id, id) in iter::once((base_root, root)).chain(pairs) {
+                let node = merge.nodes[BASE][base_id.to_index()].unwrap();
+                merge.ids[node][side] = Some(id);
+                merge.nodes[side][id.to_index()] = Some(node);
+            }
+        }
+
+        // Nodes inserted on both sides with the same key are the same node.
+        let [ours_inserted, theirs_inserted] = [OURS, THEIRS].map(|side| {
+            trees[side]
+                .root()
+                .descendants()
+                .filter(|node| merge.nodes[side][node.id.to_index()].is_none())
+                .collect::<Vec<_>>()
+        });
+        for (ours_id, theirs_id) in pair_by_key(ours_inserted, theirs_inserted, &mut *key) {
+            merge.add([None, Some(ours_id), Some(theirs_id)]);
+        }
+        for side in [OURS, THEIRS] {
+            for node in trees[side].root().descendants() {
+                if merge.nodes[side][node.id.to_index()].is_none() {
+                    let mut ids = [None; 3];
+                    ids[side] = Some(node.id);
+                    merge.add(ids);
+                }
+            }
+        }
+        merge
+    }
+
+    fn add(&mut self, ids: [Option<NodeId>; 3]) {
+        let node = self.ids.len();
+        for (side, id) in ids.iter().enumerate() {
+            if let Some(id) = id {
+                self.nodes[side][id.to_index()] = Some(node);
+            }
+        }
+        self.ids.push(ids);
+    }
+
+    fn get(&self, node: usize, side: usize) -> Option<NodeRef<'a, T>> {
+        let id = self.ids[node][side]?;
+        self.trees[side].get(id)
+    }
+
+    /// Returns the parent of a node in a tree, or `None` if the tree does not
+    /// have the node.
+    fn parent(&self, node: usize, side: usize) -> Option<Option<usize>> {
+        let parent = self.get(node, side)?.parent();
+        Some(parent.map(|parent| self.nodes[side][parent.id.to_index()].unwrap()))
+    }
+
+    /// Returns the merged order of the children of a node, given the merged
+    /// parent of each node, or `None` if both sides reordered them
+    /// differently.
+    fn order(&self, node: usize, parents: &[Option<usize>]) -> Option<Vec<usize>> {
+        let lists = [BASE, OURS, THEIRS].map(|side| {
+            let children = self
+                .get(node, side)
+                .into_iter()
+                .flat_map(|node| node.children());
+            children
+                .map(|child| self.nodes[side][child.id.to_index()].unwrap())
+                .filter(|&child| parents[child] == Some(node))
+                .collect::<Vec<_>>()
+        });
+
+        // Compares the orders of the children that are in all three lists.
+        let in_all = |child: usize| (0..3).all(|side| self.parent(child, side) == Some(Some(node)));
+        let shared = lists.clone().map(|list| {
+            list.into_iter()
+                .filter(|&child| in_all(child))
+                .collect::<Vec<_>>()
+        });
+        let (main, other) = if shared[OURS] == shared[BASE] {
+            (THEIRS, OURS)
+        } else if shared[THEIRS] == shared[BASE] || shared[THEIRS] == shared[OURS] {
+            (OURS, THEIRS)
+        } else {
+            return None;
+        };
+
+        // Children only on the other side go after their previous siblings
+        // there.
+        let mut order = lists[main].clone();
+        let mut index = 0;
+        for &child in &lists[other] {
+            match order.iter().position(|&placed| placed == child) {
+                Some(position) => index = index.max(position + 1),
+                None => {
+                    order.insert(index, child);
+                    index += 1;
+                }
+            }
+        }
+        Some(order)
+    }
+
+    fn conflict(&self, kind: ConflictKind, node: usize) -> Conflict {
+        let [base, ours, theirs] = self.ids[node];
+        Conflict {
+            kind,
+            base,
+            ours,
+            theirs,
+        }
+    }
+}
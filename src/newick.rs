@@ -0,0 +1,248 @@
+//! Newick format for phylogenetic trees.
+//!
+//! # Examples
+//!
+//! ```
+//! use ego_tree::newick::NewickNode;
+//! use ego_tree::Tree;
+//!
+//! let tree = Tree::from_newick("((A:0.1,B:0.2)C:0.3)D;").unwrap();
+//! let c = tree.root().first_child().unwrap();
+//! assert_eq!(
+//!     c.value(),
+//!     &NewickNode { label: Some("C".to_owned()), length: Some(0.3) },
+//! );
+//! assert_eq!(tree.to_newick(), "((A:0.1,B:0.2)C:0.3)D;");
+//! ```
+
+use std::fmt::{self, Write};
+
+use crate::iter::Edge;
+use crate::parse::{line_at, ParseError};
+use crate::Tree;
+
+/// Node of a Newick tree.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NewickNode {
+    /// Label of the node, if any.
+    pub label: Option<String>,
+    /// Length of the branch to the parent of the node, if any.
+    pub length: Option<f64>,
+}
+
+impl Tree<NewickNode> {
+    /// Parses a tree from a Newick string.
+    ///
+    /// Labels are either unquoted, in which case underscores stand for spaces,
+    /// or quoted with `'`, in which case `''` stands for a quote. Comments in
+    /// square brackets and whitespace outside of labels are ignored.
+    ///
+    /// Parsing is iterative, so deeply nested trees are supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is malformed, if a branch length is not
+    /// a number or if the tree is not terminated by `;`.
+    pub fn from_newick(s: &str) -> Result<Self, ParseError> {
+        let mut reader = Reader { s, pos: 0 };
+        let mut tree = Tree::new(NewickNode::default());
+        let mut id = tree.root().id;
+        let mut parent_ids = Vec::new();
+
+        loop {
+            reader.skip_whitespace()?;
+            while reader.eat('(') {
+                parent_ids.push(id);
+                id = tree.get_mut(id).unwrap().append(NewickNode::default()).id;
+                reader.skip_whitespace()?;
+            }
+            *tree.get_mut(id).unwrap().value() = reader.node()?;
+
+            loop {
+                reader.skip_whitespace()?;
+                let start = reader.pos;
+                if reader.eat(',') {
+                    let parent_id = *parent_ids
+                        .last()
+                        .ok_or_else(|| reader.error_at(start, "unexpected `,`"))?;
+                    id = tree
+                        .get_mut(parent_id)
+                        .unwrap()
+                        .append(NewickNode::default())
+                        .id;
+                    break;
+                } else if reader.eat(')') {
+                    id = parent_ids
+                        .pop()
+                        .ok_or_else(|| reader.error_at(start, "unmatched `)`"))?;
+                    *tree.get_mut(id).unwrap().value() = reader.node()?;
+                } else if reader.eat(';') {
+                    if !parent_ids.is_empty() {
+                        return Err(reader.error_at(start, "expected `)`"));
+                    }
+                    reader.skip_whitespace()?;
+                    if !reader.rest().is_empty() {
+                        return Err(reader.error("unexpected input after `;`"));
+                    }
+                    return Ok(tree);
+                } else if parent_ids.is_empty() {
+                    return Err(reader.error("expected `;`"));
+                } else {
+                    return Err(reader.error("expected `,` or `)`"));
+                }
+            }
+        }
+    }
+
+    /// Returns the tree as a Newick string.
+    pub fn to_newick(&self) -> String {
+        let mut newick = String::new();
+        self.write_newick(&mut newick)
+            .expect("writing to a String cannot fail");
+        newick
+    }
+
+    /// Writes the tree as a Newick string.
+    ///
+    /// Labels are quoted if needed. Orphans are not written.
+    pub fn write_newick<W: Write>(&self, writer: &mut W) -> fmt::Result {
+        for edge in self.root().traverse() {
+            match edge {
+                Edge::Open(node) => {
+                    if node.prev_sibling().is_some() {
+                        writer.write_char(',')?;
+                    }
+                    if node.has_children() {
+                        writer.write_char('(')?;
+                    }
+                }
+                Edge::Close(node) => {
+                    if node.has_children() {
+                        writer.write_char(')')?;
+                    }
+                    let NewickNode { label, length } = node.value();
+                    if let Some(label) = label {
+                        write_label(writer, label)?;
+                    }
+                    if let Some(length) = length {
+                        write!(writer, ":{}", length)?;
+                    }
+                }
+            }
+        }
+        writer.write_char(';')
+    }
+}
+
+/// Returns true if `c` cannot appear in an unquoted label.
+fn is_special(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | '\'' | ':' | ';' | ',')
+}
+
+/// Writes a label, quoted if needed.
+fn write_label<W: Write>(writer: &mut W, label: &str) -> fmt::Result {
+    if !label.is_empty() && !label.chars().any(|c| is_special(c) || c == '_') {
+        return writer.write_str(label);
+    }
+    writer.write_char('\'')?;
+    for c in label.chars() {
+        if c == '\'' {
+            writer.write_char('\'')?;
+        }
+        writer.write_char(c)?;
+    }
+    writer.write_char('\'')
+}
+
+/// Newick reader.
+struct Reader<'s> {
+    s: &'s str,
+    pos: usize,
+}
+
+impl<'s> Reader<'s> {
+    fn rest(&self) -> &'s str {
+        &self.s[self.pos..]
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> ParseError {
+        ParseError::new(line_at(self.s, pos), message)
+    }
+
+    /// Skips whitespace and comments.
+    fn skip_whitespace(&mut self) -> Result<(), ParseError> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if !trimmed.starts_with('[') {
+                return Ok(());
+            }
+            match trimmed.find(']') {
+                Some(end) => self.pos += end + 1,
+                None => return Err(self.error("unterminated comment")),
+            }
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.rest().starts_with(c);
+        if found {
+            self.pos += c.len_utf8();
+        }
+        found
+    }
+
+    /// Reads the unquoted text up to the next special character.
+    fn unquoted(&mut self) -> &'s str {
+        let rest = self.rest();
+        let end = rest.find(is_special).unwrap_or(rest.len());
+        self.pos += end;
+        &rest[..end]
+    }
+
+    /// Reads the optional label and branch length of a node.
+    fn node(&mut self) -> Result<NewickNode, ParseError> {
+        self.skip_whitespace()?;
+        let start = self.pos;
+        let label = if self.eat('\'') {
+            let mut label = String::new();
+            loop {
+                let rest = self.rest();
+                let end = rest
+                    .find('\'')
+                    .ok_or_else(|| self.error_at(start, "unterminated label"))?;
+                label.push_str(&rest[..end]);
+                self.pos += end + 1;
+                if !self.eat('\'') {
+                    break Some(label);
+                }
+                label.push('\'');
+            }
+        } else {
+            Some(self.unquoted().replace('_', " ")).filter(|label| !label.is_empty())
+        };
+
+        self.skip_whitespace()?;
+        let length = if self.eat(':') {
+            self.skip_whitespace()?;
+            let start = self.pos;
+            let length = self.unquoted();
+            let length = length.parse().map_err(|err| {
+                self.error_at(
+                    start,
+                    format!("invalid branch length {:?}: {}", length, err),
+                )
+            })?;
+            Some(length)
+        } else {
+            None
+        };
+
+        Ok(NewickNode { label, length })
+    }
+}
@@ -0,0 +1,137 @@
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::Index;
+
+use crate::NodeId;
+
+/// Map from node IDs to values.
+///
+/// Backed by a `Vec` indexed like the nodes of a tree, so lookups are
+/// constant-time. Maps with the same entries are equal, whatever their
+/// capacity.
+#[derive(Debug, Clone)]
+pub struct NodeMap<V> {
+    vec: Vec<Option<V>>,
+    len: usize,
+}
+
+impl<V> Default for NodeMap<V> {
+    fn default() -> Self {
+        NodeMap {
+            vec: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<V> NodeMap<V> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        NodeMap::default()
+    }
+
+    /// Creates an empty map with room for the nodes of a tree of `capacity`
+    /// nodes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        NodeMap {
+            vec: Vec::with_capacity(capacity),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if there is a value for `id`.
+    pub fn contains_key(&self, id: NodeId) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Returns the value for `id`.
+    pub fn get(&self, id: NodeId) -> Option<&V> {
+        self.vec.get(id.to_index()).and_then(Option::as_ref)
+    }
+
+    /// Returns the value for `id` mutably.
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut V> {
+        self.vec.get_mut(id.to_index()).and_then(Option::as_mut)
+    }
+
+    /// Sets the value for `id`, returning the previous one.
+    pub fn insert(&mut self, id: NodeId, value: V) -> Option<V> {
+        let index = id.to_index();
+        if index >= self.vec.len() {
+            self.vec.resize_with(index + 1, || None);
+        }
+        let old = self.vec[index].replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Removes the value for `id`, returning it.
+    pub fn remove(&mut self, id: NodeId) -> Option<V> {
+        let old = self.vec.get_mut(id.to_index()).and_then(Option::take);
+        if old.is_some() {
+            self.len -= 1;
+        }
+        old
+    }
+
+    /// Returns an iterator over the entries, in order of node IDs.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &V)> + '_ {
+        self.vec.iter().enumerate().filter_map(|(index, value)| {
+            // Safety: `index` is the index of an element of a `Vec`.
+            let id = unsafe { NodeId::from_index(index) };
+            value.as_ref().map(|value| (id, value))
+        })
+    }
+}
+
+impl<V: PartialEq> PartialEq for NodeMap<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<V: Eq> Eq for NodeMap<V> {}
+
+impl<V: Hash> Hash for NodeMap<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len);
+        for entry in self.iter() {
+            entry.hash(state);
+        }
+    }
+}
+
+impl<V> Index<NodeId> for NodeMap<V> {
+    type Output = V;
+
+    /// Returns the value for `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no value for `id`.
+    fn index(&self, id: NodeId) -> &V {
+        self.get(id).expect("no value for the node ID")
+    }
+}
+
+impl<V> FromIterator<(NodeId, V)> for NodeMap<V> {
+    fn from_iter<I: IntoIterator<Item = (NodeId, V)>>(iter: I) -> Self {
+        let mut map = NodeMap::new();
+        for (id, value) in iter {
+            map.insert(id, value);
+        }
+        map
+    }
+}
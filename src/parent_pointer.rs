@@ -0,0 +1,235 @@
+//! Conversions between trees and `(id, parent_id, value)` rows.
+//!
+//! # Examples
+//!
+//! ```
+//! # use ego_tree::tree;
+//! use ego_tree::Tree;
+//!
+//! let rows = vec![(3, Some(1), 'c'), (1, None, 'a'), (2, Some(1), 'b')];
+//! let tree = Tree::from_parent_pointers(rows).unwrap();
+//! assert_eq!(format!("{:#?}", tree), format!("{:#?}", tree!('a' => { 'c', 'b' })));
+//!
+//! let rows = vec![(3, Some(1), 0, 'c'), (1, None, 0, 'a'), (2, Some(1), -1, 'b')];
+//! let tree = Tree::from_ordered_parent_pointers(rows).unwrap();
+//! assert_eq!(format!("{:#?}", tree), format!("{:#?}", tree!('a' => { 'b', 'c' })));
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::Hash;
+
+use crate::iter::Edge;
+use crate::{NodeId, Tree};
+
+/// Error returned when building a tree from parent pointers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ParentPointerError<K> {
+    /// There are no rows.
+    Empty,
+    /// Two rows have the same ID.
+    DuplicateId(K),
+    /// Two rows have no parent.
+    MultipleRoots(K, K),
+    /// A row has a parent ID that is not the ID of any row.
+    MissingParent {
+        /// ID of the row.
+        id: K,
+        /// Parent ID of the row.
+        parent_id: K,
+    },
+    /// A row is its own ancestor.
+    Cycle(K),
+}
+
+impl<K: Debug> Display for ParentPointerError<K> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ParentPointerError::Empty => write!(f, "no rows"),
+            ParentPointerError::DuplicateId(id) => write!(f, "duplicate ID {:?}", id),
+            ParentPointerError::MultipleRoots(a, b) => {
+                write!(f, "multiple roots {:?} and {:?}", a, b)
+            }
+            ParentPointerError::MissingParent { id, parent_id } => {
+                write!(f, "missing parent {:?} of {:?}", parent_id, id)
+            }
+            ParentPointerError::Cycle(id) => write!(f, "cycle through {:?}", id),
+        }
+    }
+}
+
+impl<K: Debug> Error for ParentPointerError<K> {}
+
+impl<T> Tree<T> {
+    /// Builds a tree from `(id, parent_id, value)` rows.
+    ///
+    /// The row without a parent is the root. Siblings are in row order, and
+    /// the nodes are in tree order in the returned tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are no rows, if IDs are not unique, if there
+    /// is not exactly one root, if a parent is missing or if there is a cycle.
+    pub fn from_parent_pointers<K, I>(rows: I) -> Result<Self, ParentPointerError<K>>
+    where
+        K: Hash + Eq + Clone,
+        I: IntoIterator<Item = (K, Option<K>, T)>,
+    {
+        let rows = rows
+            .into_iter()
+            .map(|(id, parent_id, value)| (id, parent_id, (), value));
+        from_rows(rows)
+    }
+
+    /// Builds a tree from `(id, parent_id, position, value)` rows.
+    ///
+    /// Siblings are ordered by position, then by row order. Positions need
+    /// not be contiguous.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Tree::from_parent_pointers`].
+    pub fn from_ordered_parent_pointers<K, P, I>(rows: I) -> Result<Self, ParentPointerError<K>>
+    where
+        K: Hash + Eq + Clone,
+        P: Ord,
+        I: IntoIterator<Item = (K, Option<K>, P, T)>,
+    {
+        from_rows(rows)
+    }
+
+    /// Returns `(id, parent_id, value)` rows for the nodes of the tree.
+    ///
+    /// Rows are in tree order, so siblings are in order. Orphans are not
+    /// included.
+    pub fn to_parent_pointers(&self) -> Vec<(NodeId, Option<NodeId>, &T)> {
+        self.root()
+            .descendants()
+            .map(|node| {
+                (
+                    node.id(),
+                    node.parent().map(|parent| parent.id()),
+                    node.value(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns `(id, parent_id, position, value)` rows for the nodes of the
+    /// tree, where position is the index of the node among its siblings.
+    ///
+    /// Rows are in tree order. Orphans are not included.
+    pub fn to_ordered_parent_pointers(&self) -> Vec<(NodeId, Option<NodeId>, usize, &T)> {
+        let mut rows = Vec::new();
+        let mut positions = vec![0];
+        for edge in self.root().traverse() {
+            match edge {
+                Edge::Open(node) => {
+                    let position = positions.last_mut().unwrap();
+                    let parent_id = node.parent().map(|parent| parent.id());
+                    rows.push((node.id(), parent_id, *position, node.value()));
+                    *position += 1;
+                    positions.push(0);
+                }
+                Edge::Close(_) => {
+                    positions.pop();
+                }
+            }
+        }
+        rows
+    }
+}
+
+fn from_rows<K, P, T, I>(rows: I) -> Result<Tree<T>, ParentPointerError<K>>
+where
+    K: Hash + Eq + Clone,
+    P: Ord,
+    I: IntoIterator<Item = (K, Option<K>, P, T)>,
+{
+    let mut ids = Vec::new();
+    let mut parent_ids = Vec::new();
+    let mut positions = Vec::new();
+    let mut values = Vec::new();
+    let mut indices = HashMap::new();
+    for (index, (id, parent_id, position, value)) in rows.into_iter().enumerate() {
+        if indices.insert(id.clone(), index).is_some() {
+            return Err(ParentPointerError::DuplicateId(id));
+        }
+        ids.push(id);
+        parent_ids.push(parent_id);
+        positions.push(position);
+        values.push(Some(value));
+    }
+
+    let mut root: Option<usize> = None;
+    let mut parents = Vec::with_capacity(ids.len());
+    let mut children = vec![Vec::new(); ids.len()];
+    for (index, parent_id) in parent_ids.iter().enumerate() {
+        let parent = match parent_id {
+            Some(parent_id) => match indices.get(parent_id) {
+                Some(&parent) => Some(parent),
+                None => {
+                    return Err(ParentPointerError::MissingParent {
+                        id: ids[index].clone(),
+                        parent_id: parent_id.clone(),
+                    })
+                }
+            },
+            None => match root {
+                Some(root) => {
+                    return Err(ParentPointerError::MultipleRoots(
+                        ids[root].clone(),
+                        ids[index].clone(),
+                    ))
+                }
+                None => {
+                    root = Some(index);
+                    None
+                }
+            },
+        };
+        if let Some(parent) = parent {
+            children[parent].push(index);
+        }
+        parents.push(parent);
+    }
+
+    let root = match root {
+        Some(root) => root,
+        None if ids.is_empty() => return Err(ParentPointerError::Empty),
+        // Every node has a parent, so following parents from any node cycles.
+        None => return Err(ParentPointerError::Cycle(cycle_id(&ids, &parents, 0))),
+    };
+
+    let mut tree = Tree::with_capacity(values[root].take().unwrap(), ids.len());
+    let mut stack = Vec::new();
+    let mut push_children = |stack: &mut Vec<_>, index: usize, id: NodeId| {
+        let children = &mut children[index];
+        children.sort_by(|&a, &b| positions[a].cmp(&positions[b]));
+        stack.extend(children.iter().rev().map(|&child| (child, id)));
+    };
+    push_children(&mut stack, root, tree.root().id());
+    while let Some((index, parent_id)) = stack.pop() {
+        let value = values[index].take().unwrap();
+        let id = tree.get_mut(parent_id).unwrap().append(value).id();
+        push_children(&mut stack, index, id);
+    }
+
+    // Nodes not reached from the root have a cycle among their ancestors.
+    match values.iter().position(Option::is_some) {
+        Some(index) => Err(ParentPointerError::Cycle(cycle_id(&ids, &parents, index))),
+        None => Ok(tree),
+    }
+}
+
+/// Returns the ID of a node on the cycle reached by following parents from
+/// `index`.
+fn cycle_id<K: Clone>(ids: &[K], parents: &[Option<usize>], mut index: usize) -> K {
+    let mut visited = vec![false; ids.len()];
+    while !visited[index] {
+        visited[index] = true;
+        index = parents[index].expect("following parents reached the root");
+    }
+    ids[index].clone()
+}
@@ -0,0 +1,378 @@
+//! Parsing trees from text.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{NodeId, Tree};
+
+/// Error returned when parsing a tree from text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseError {
+    line: usize,
+    message: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(line: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the line of the error, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the description of the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Returns the line of the byte at `pos` in `s`, starting at 1.
+///
+/// Counting takes linear time, so parsers only do it to build errors.
+pub(crate) fn line_at(s: &str, pos: usize) -> usize {
+    s.as_bytes()[..pos].iter().filter(|&&b| b == b'\n').count() + 1
+}
+
+/// Parses a value with its `FromStr` implementation, returning the message of
+/// the error if it fails.
+fn parse_value<T>(s: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    s.parse()
+        .map_err(|err| format!("invalid value {:?}: {}", s, err))
+}
+
+impl<T> Tree<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    /// Parses a tree from its `Display` output.
+    ///
+    /// The first line is the root and each following line is a node, with
+    /// the `├── `, `└── ` and `│   ` indentation tokens of `Display`. Values
+    /// are parsed with their `FromStr` implementation.
+    ///
+    /// Continuation lines of multi-line labels, indented under their node
+    /// without a branch, are folded back into the label. Continuation lines
+    /// starting with `├── ` or `└── ` look like nodes indented too deep and are
+    /// rejected, so such labels do not round-trip.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is empty, if the indentation tokens
+    /// are inconsistent, or if a value fails to parse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ego_tree::{tree, Tree};
+    /// let tree = Tree::<String>::from_display_str(
+    ///     "root
+    /// ├── a
+    /// │   └── child 1
+    /// └── b",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(format!("{tree}"), "root\n├── a\n│   └── child 1\n└── b\n");
+    /// ```
+    pub fn from_display_str(s: &str) -> Result<Self, ParseError> {
+        let mut lines = s.lines().zip(1..);
+        let (root, _) = lines
+            .next()
+            .ok_or_else(|| ParseError::new(1, "expected a root node"))?;
+
+        // Nodes in tree order: index of the parent, label and first line.
+        let mut nodes = vec![(0, root.to_owned(), 1)];
+        // Path from the root to the last node: index, whether it is the last
+        // of its siblings, and its line.
+        let mut path: Vec<(usize, bool, usize)> = vec![(0, true, 1)];
+
+        for (line, number) in lines {
+            let mut rest = line;
+            let mut depth = 0;
+            // Whether the node is the last of its siblings, or `None` for a
+            // continuation line of the label of the last node.
+            let last = loop {
+                if let Some(value) = rest.strip_prefix("├── ") {
+                    rest = value;
+                    break Some(false);
+                }
+                if let Some(value) = rest.strip_prefix("└── ") {
+                    rest = value;
+                    break Some(true);
+                }
+                let ancestor_last = if let Some(tail) = rest.strip_prefix("│   ") {
+                    rest = tail;
+                    false
+                } else if let Some(tail) = rest.strip_prefix("    ") {
+                    rest = tail;
+                    true
+                } else {
+                    return Err(ParseError::new(number, "expected `├── ` or `└── `"));
+                };
+                depth += 1;
+                match path.get(depth) {
+                    Some(&(_, last, _)) if last == ancestor_last => {}
+                    Some(_) => {
+                        return Err(ParseError::new(
+                            number,
+                            "indentation does not match the siblings of an ancestor",
+                        ));
+                    }
+                    None if rest.starts_with("├── ") || rest.starts_with("└── ") => {
+                        return Err(ParseError::new(number, "indentation too deep"));
+                    }
+                    None => break None,
+                }
+            };
+
+            let Some(last) = last else {
+                let label = &mut nodes[path[path.len() - 1].0].1;
+                label.push('\n');
+                label.push_str(rest);
+                continue;
+            };
+            if let Some(&(_, true, _)) = path.get(depth + 1) {
+                return Err(ParseError::new(number, "node after the last sibling"));
+            }
+            if let Some(&(_, _, line)) = path.iter().skip(depth + 2).find(|(_, last, _)| !last) {
+                return Err(ParseError::new(line, "expected a next sibling"));
+            }
+            path.truncate(depth + 1);
+
+            path.push((nodes.len(), last, number));
+            nodes.push((path[depth].0, rest.to_owned(), number));
+        }
+
+        if let Some(&(_, _, line)) = path.iter().find(|(_, last, _)| !last) {
+            return Err(ParseError::new(line, "expected a next sibling"));
+        }
+
+        let mut ids: Vec<NodeId> = Vec::with_capacity(nodes.len());
+        let mut tree: Option<Tree<T>> = None;
+        for (parent, label, number) in nodes {
+            let value = parse_value(&label).map_err(|message| ParseError::new(number, message))?;
+            let id = match &mut tree {
+                Some(tree) => tree.get_mut(ids[parent]).unwrap().append(value).id,
+                None => tree.insert(Tree::new(value)).root().id,
+            };
+            ids.push(id);
+        }
+        Ok(tree.unwrap())
+    }
+}
+
+impl<T> Tree<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    /// Parses a tree from the syntax of the `tree!` macro, as printed by the
+    /// alternate `Debug` format.
+    ///
+    /// The `Tree { ... }` wrapper of the `Debug` output is optional. Values
+    /// are literals: quoted strings and characters are unescaped before being
+    /// parsed with their `FromStr` implementation, other values are parsed as
+    /// they are. This makes the `Debug` output of trees of strings, characters
+    /// and numbers round-trip exactly.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the syntax is invalid or if a value fails to
+    /// parse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ego_tree::{tree, Tree};
+    /// let tree = tree!('a' => { 'b', 'c' => { 'd' } });
+    /// let repr = format!("{:#?}", tree);
+    /// assert_eq!(repr, "Tree { 'a' => { 'b', 'c' => { 'd' } } }");
+    ///
+    /// let parsed = Tree::<char>::from_macro_str(&repr).unwrap();
+    /// assert_eq!(format!("{:#?}", parsed), repr);
+    ///
+    /// let parsed = Tree::<String>::from_macro_str(r#""a" => { "b", "c", }"#).unwrap();
+    /// assert_eq!(parsed.root().value(), "a");
+    /// ```
+    pub fn from_macro_str(s: &str) -> Result<Self, ParseError> {
+        let mut parser = MacroParser { s, pos: 0 };
+
+        parser.skip_whitespace();
+        let mut wrapped =
+            parser.rest().starts_with("Tree") && parser.rest()[4..].trim_start().starts_with('{');
+        if wrapped {
+            parser.pos += 4;
+            parser.skip_whitespace();
+            parser.pos += 1;
+            parser.skip_whitespace();
+        }
+
+        let mut tree = Tree::new(parser.value()?);
+        let mut last_id = tree.root().id;
+        let mut parent_ids = Vec::new();
+        let mut expect_value = false;
+        // Whether the last token is a value, which `=>` may follow.
+        let mut after_value = true;
+
+        loop {
+            parser.skip_whitespace();
+            if parser.rest().is_empty() {
+                if wrapped || !parent_ids.is_empty() {
+                    return Err(parser.error("expected `}`"));
+                }
+                return Ok(tree);
+            }
+
+            let was_after_value = after_value;
+            after_value = false;
+            if expect_value {
+                if parser.eat("}") {
+                    parent_ids.pop();
+                } else {
+                    let value = parser.value()?;
+                    let parent_id = *parent_ids.last().unwrap();
+                    last_id = tree.get_mut(parent_id).unwrap().append(value).id;
+                    after_value = true;
+                }
+                expect_value = false;
+            } else if was_after_value && parser.eat("=>") {
+                parser.skip_whitespace();
+                if !parser.eat("{") {
+                    return Err(parser.error("expected `{`"));
+                }
+                parent_ids.push(last_id);
+                expect_value = true;
+            } else if !parent_ids.is_empty() && parser.eat(",") {
+                expect_value = true;
+            } else if !parent_ids.is_empty() && parser.eat("}") {
+                parent_ids.pop();
+            } else if wrapped && parser.eat("}") {
+                wrapped = false;
+            } else if parent_ids.is_empty() && !wrapped {
+                return Err(parser.error("unexpected input after the tree"));
+            } else {
+                return Err(parser.error("expected `=>`, `,` or `}`"));
+            }
+        }
+    }
+}
+
+/// Parser of the `tree!` macro syntax.
+struct MacroParser<'s> {
+    s: &'s str,
+    pos: usize,
+}
+
+impl<'s> MacroParser<'s> {
+    fn rest(&self) -> &'s str {
+        &self.s[self.pos..]
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> ParseError {
+        ParseError::new(line_at(self.s, pos), message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.s.len() - self.rest().trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    /// Parses a quoted or bare value.
+    fn value<T>(&mut self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let start = self.pos;
+        let rest = self.rest();
+        let raw = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let mut value = String::new();
+                let mut chars = rest.char_indices().skip(1);
+                loop {
+                    match chars.next() {
+                        Some((i, c)) if c == quote => {
+                            self.pos += i + 1;
+                            break;
+                        }
+                        Some((_, '\\')) => value.push(
+                            unescape(&mut chars)
+                                .ok_or_else(|| self.error("invalid escape sequence"))?,
+                        ),
+                        Some((_, c)) => value.push(c),
+                        None => {
+                            self.pos = self.s.len();
+                            return Err(self.error_at(start, "unterminated literal"));
+                        }
+                    }
+                }
+                return parse_value(&value).map_err(|message| self.error_at(start, message));
+            }
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || matches!(c, ',' | '{' | '}'))
+                    .unwrap_or(rest.len());
+                let end = rest[..end].find("=>").unwrap_or(end);
+                &rest[..end]
+            }
+        };
+        if raw.is_empty() {
+            return Err(self.error("expected a value"));
+        }
+        self.pos += raw.len();
+        parse_value(raw).map_err(|message| self.error_at(start, message))
+    }
+}
+
+/// Unescapes a character escape sequence, after its backslash.
+fn unescape(chars: &mut impl Iterator<Item = (usize, char)>) -> Option<char> {
+    match chars.next()?.1 {
+        'n' => Some('\n'),
+        'r' => Some('\r'),
+        't' => Some('\t'),
+        '0' => Some('\0'),
+        c @ ('\\' | '\'' | '"') => Some(c),
+        'u' => {
+            if chars.next()?.1 != '{' {
+                return None;
+            }
+            let mut code = 0;
+            loop {
+                match chars.next()?.1 {
+                    '}' => return char::from_u32(code),
+                    c => code = code.checked_mul(16)? + c.to_digit(16)?,
+                }
+            }
+        }
+        _ => None,
+    }
+}
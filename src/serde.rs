@@ -0,0 +1,286 @@
+//! Serde support.
+//!
+//! `Tree<T>` and `NodeRef<T>` are serialized in a nested form, each node
+//! being a `{ "value": ..., "children": [...] }` struct. Only the nodes
+//! reachable from the root are serialized; orphans and `NodeId`s are not
+//! preserved. See [`flat`] for a representation which preserves both.
+//!
+//! `NodeId`s are serialized as their index into the tree.
+//!
+//! Deserialized nodes are linked into the tree as soon as they are read,
+//! without building an intermediate nested structure.
+//!
+//! The nested form cannot be read or written iteratively: serde drives nested
+//! values through nested calls, so both directions recurse once per level of
+//! the tree. Deserialization fails with an error for trees deeper than
+//! [`MAX_DEPTH`] rather than overflowing the stack, and deserializers may stop
+//! earlier. `serde_json::from_str` has a recursion limit of 128 and each level
+//! of the tree takes two (an object and its `children` array), so it reads
+//! trees at most 62 levels below the root. Serialization is not bounded. Use
+//! [`flat`], which is iterative both ways, for deeper trees.
+//!
+//! # Examples
+//!
+//! ```
+//! # use ego_tree::{tree, Tree};
+//! let tree = tree!('a' => { 'b', 'c' });
+//! let json = serde_json::to_string(&tree).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"value":"a","children":[{"value":"b","children":[]},{"value":"c","children":[]}]}"#
+//! );
+//! let tree: Tree<char> = serde_json::from_str(&json).unwrap();
+//! ```
+
+use std::fmt::{self, Formatter};
+use std::num::NonZeroUsize;
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde::Deserialize;
+
+use crate::{Node, NodeId, NodeRef, Tree};
+
+pub mod flat;
+
+const FIELDS: &[&str] = &["value", "children"];
+
+/// Maximum depth of a deserialized tree in the nested form, the root being at
+/// depth 0.
+pub const MAX_DEPTH: usize = 512;
+
+impl Serialize for NodeId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.to_index() as u64)
+    }
+}
+
+impl<'de> Deserialize<'de> for NodeId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let index = u64::deserialize(deserializer)?;
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| NonZeroUsize::new(index.wrapping_add(1)))
+            .map(NodeId)
+            .ok_or_else(|| {
+                de::Error::invalid_value(de::Unexpected::Unsigned(index), &"a node index")
+            })
+    }
+}
+
+impl<T: Serialize> Serialize for Tree<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.root().serialize(serializer)
+    }
+}
+
+impl<'a, T: Serialize> Serialize for NodeRef<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Node", 2)?;
+        state.serialize_field("value", self.value())?;
+        state.serialize_field("children", &Children(*self))?;
+        state.end()
+    }
+}
+
+/// Serializes the children of a node as a sequence.
+struct Children<'a, T>(NodeRef<'a, T>);
+
+impl<'a, T: Serialize> Serialize for Children<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.children())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Tree<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut vec = Vec::new();
+        NodeSeed {
+            vec: &mut vec,
+            depth: 0,
+        }
+        .deserialize(deserializer)?;
+
+        // Nodes are pushed after their children, so the root comes last.
+        // Rotate it to the front and shift every other ID by one.
+        let len = vec.len();
+        let shift = |id: NodeId| unsafe { NodeId::from_index((id.to_index() + 1) % len) };
+        for node in &mut vec {
+            node.parent = node.parent.map(shift);
+            node.prev_sibling = node.prev_sibling.map(shift);
+            node.next_sibling = node.next_sibling.map(shift);
+            node.children = node.children.map(|(first_child_id, last_child_id)| {
+                (shift(first_child_id), shift(last_child_id))
+            });
+        }
+        vec.rotate_right(1);
+
+        Ok(Tree { vec })
+    }
+}
+
+/// Deserializes a node and its descendants into `vec`, returning its ID.
+struct NodeSeed<'v, T> {
+    vec: &'v mut Vec<Node<T>>,
+    /// Depth of the node.
+    depth: usize,
+}
+
+impl<'v, 'de, T: Deserialize<'de>> DeserializeSeed<'de> for NodeSeed<'v, T> {
+    type Value = NodeId;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<NodeId, D::Error> {
+        if self.depth > MAX_DEPTH {
+            return Err(de::Error::custom(format_args!(
+                "tree deeper than {} levels, use `ego_tree::serde::flat` instead",
+                MAX_DEPTH
+            )));
+        }
+        deserializer.deserialize_struct("Node", FIELDS, self)
+    }
+}
+
+impl<'v, 'de, T: Deserialize<'de>> NodeSeed<'v, T> {
+    fn push(self, value: T, child_ids: Vec<NodeId>) -> NodeId {
+        let id = unsafe { NodeId::from_index(self.vec.len()) };
+        let mut node = Node::new(value);
+        node.children = child_ids
+            .first()
+            .zip(child_ids.last())
+            .map(|(&f, &l)| (f, l));
+        self.vec.push(node);
+
+        for (i, &child_id) in child_ids.iter().enumerate() {
+            let child = &mut self.vec[child_id.to_index()];
+            child.parent = Some(id);
+            child.prev_sibling = i.checked_sub(1).map(|i| child_ids[i]);
+            child.next_sibling = child_ids.get(i + 1).copied();
+        }
+        id
+    }
+}
+
+impl<'v, 'de, T: Deserialize<'de>> Visitor<'de> for NodeSeed<'v, T> {
+    type Value = NodeId;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("a tree node")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<NodeId, A::Error> {
+        let value = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let child_ids = seq
+            .next_element_seed(ChildrenSeed {
+                vec: &mut *self.vec,
+                depth: self.depth + 1,
+            })?
+            .unwrap_or_default();
+        Ok(self.push(value, child_ids))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<NodeId, A::Error> {
+        let mut value = None;
+        let mut child_ids = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::Value if value.is_some() => {
+                    return Err(de::Error::duplicate_field("value"));
+                }
+                Field::Value => value = Some(map.next_value()?),
+                Field::Children if child_ids.is_some() => {
+                    return Err(de::Error::duplicate_field("children"));
+                }
+                Field::Children => {
+                    child_ids = Some(map.next_value_seed(ChildrenSeed {
+                        vec: &mut *self.vec,
+                        depth: self.depth + 1,
+                    })?);
+                }
+            }
+        }
+        let value = value.ok_or_else(|| de::Error::missing_field("value"))?;
+        Ok(self.push(value, child_ids.unwrap_or_default()))
+    }
+}
+
+/// Deserializes a sequence of nodes into `vec`, returning their IDs.
+struct ChildrenSeed<'v, T> {
+    vec: &'v mut Vec<Node<T>>,
+    /// Depth of the nodes.
+    depth: usize,
+}
+
+impl<'v, 'de, T: Deserialize<'de>> DeserializeSeed<'de> for ChildrenSeed<'v, T> {
+    type Value = Vec<NodeId>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Vec<NodeId>, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'v, 'de, T: Deserialize<'de>> Visitor<'de> for ChildrenSeed<'v, T> {
+    type Value = Vec<NodeId>;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("a sequence of tree nodes")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<NodeId>, A::Error> {
+        let mut child_ids = Vec::new();
+        while let Some(id) = seq.next_element_seed(NodeSeed {
+            vec: &mut *self.vec,
+            depth: self.depth,
+        })? {
+            child_ids.push(id);
+        }
+        Ok(child_ids)
+    }
+}
+
+/// Field of a serialized node.
+enum Field {
+    Value,
+    Children,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct FieldVisitor;
+
+impl<'de> Visitor<'de> for FieldVisitor {
+    type Value = Field;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("`value` or `children`")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Field, E> {
+        match v {
+            0 => Ok(Field::Value),
+            1 => Ok(Field::Children),
+            _ => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Field, E> {
+        match v {
+            "value" => Ok(Field::Value),
+            "children" => Ok(Field::Children),
+            _ => Err(E::unknown_field(v, FIELDS)),
+        }
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Field, E> {
+        match v {
+            b"value" => Ok(Field::Value),
+            b"children" => Ok(Field::Children),
+            _ => Err(E::unknown_field(&String::from_utf8_lossy(v), FIELDS)),
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! Flat serde representation, preserving `NodeId`s and orphans.
+//!
+//! The tree is serialized as a sequence of all its nodes in insert order,
+//! each node being a struct of its `parent`, `prev_sibling`, `next_sibling`,
+//! `children` and `value`. Node links are validated on deserialization,
+//! rejecting out of bounds IDs, asymmetric links and cycles, so that every
+//! `NodeId` of the serialized tree refers to the same node once deserialized.
+//!
+//! Use it with `#[serde(with = "ego_tree::serde::flat")]`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use ego_tree::tree;
+//! use ego_tree::serde::flat;
+//!
+//! let mut tree = tree!('a' => { 'b' });
+//! let orphan_id = tree.orphan('c').id();
+//!
+//! let mut json = Vec::new();
+//! flat::serialize(&tree, &mut serde_json::Serializer::new(&mut json)).unwrap();
+//! let json = String::from_utf8(json).unwrap();
+//! assert_eq!(
+//!     json,
+//!     concat!(
+//!         r#"[{"parent":null,"prev_sibling":null,"next_sibling":null,"children":[1,1],"value":"a"},"#,
+//!         r#"{"parent":0,"prev_sibling":null,"next_sibling":null,"children":null,"value":"b"},"#,
+//!         r#"{"parent":null,"prev_sibling":null,"next_sibling":null,"children":null,"value":"c"}]"#,
+//!     )
+//! );
+//!
+//! let de: ego_tree::Tree<char> =
+//!     flat::deserialize(&mut serde_json::Deserializer::from_str(&json)).unwrap();
+//! assert_eq!(de, tree);
+//! assert_eq!(&'c', de.get(orphan_id).unwrap().value());
+//! ```
+
+use std::fmt::{self, Formatter};
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::{Node, NodeId, Tree};
+
+const FIELDS: &[&str] = &[
+    "parent",
+    "prev_sibling",
+    "next_sibling",
+    "children",
+    "value",
+];
+
+/// Serializes a tree in the flat representation.
+pub fn serialize<T, S>(tree: &Tree<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    serializer.collect_seq(&tree.vec)
+}
+
+/// Deserializes a tree from the flat representation.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Tree<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let vec = Vec::<Node<T>>::deserialize(deserializer)?;
+    validate(&vec).map_err(de::Error::custom)?;
+    Ok(Tree { vec })
+}
+
+impl<T: Serialize> Serialize for Node<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Node", 5)?;
+        state.serialize_field("parent", &self.parent)?;
+        state.serialize_field("prev_sibling", &self.prev_sibling)?;
+        state.serialize_field("next_sibling", &self.next_sibling)?;
+        state.serialize_field("children", &self.children)?;
+        state.serialize_field("value", &self.value)?;
+        state.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Node<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("Node", FIELDS, NodeVisitor(PhantomData))
+    }
+}
+
+struct NodeVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for NodeVisitor<T> {
+    type Value = Node<T>;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("a tree node")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Node<T>, A::Error> {
+        let parent = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let prev_sibling = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let next_sibling = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        let children = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(3, &self))?;
+        let value = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(4, &self))?;
+        Ok(Node {
+            parent,
+            prev_sibling,
+            next_sibling,
+            children,
+            value,
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Node<T>, A::Error> {
+        let mut parent = None;
+        let mut prev_sibling = None;
+        let mut next_sibling = None;
+        let mut children = None;
+        let mut value = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "parent" if parent.is_none() => parent = Some(map.next_value()?),
+                "prev_sibling" if prev_sibling.is_none() => prev_sibling = Some(map.next_value()?),
+                "next_sibling" if next_sibling.is_none() => next_sibling = Some(map.next_value()?),
+                "children" if children.is_none() => children = Some(map.next_value()?),
+                "value" if value.is_none() => value = Some(map.next_value()?),
+                key if FIELDS.contains(&key) => {
+                    return Err(de::Error::custom(format_args!("duplicate field `{}`", key)));
+                }
+                key => return Err(de::Error::unknown_field(key, FIELDS)),
+            }
+        }
+        Ok(Node {
+            parent: parent.unwrap_or_default(),
+            prev_sibling: prev_sibling.unwrap_or_default(),
+            next_sibling: next_sibling.unwrap_or_default(),
+            children: children.unwrap_or_default(),
+            value: value.ok_or_else(|| de::Error::missing_field("value"))?,
+        })
+    }
+}
+
+/// Checks that node links are in bounds, symmetric and acyclic.
+fn validate<T>(vec: &[Node<T>]) -> Result<(), String> {
+    if vec.is_empty() {
+        return Err("expected at least a root node".to_owned());
+    }
+
+    let node = |id: NodeId| &vec[id.to_index()];
+    for (index, node) in vec.iter().enumerate() {
+        let links = [node.parent, node.prev_sibling, node.next_sibling];
+        let children = node
+            .children
+            .map_or([None, None], |(f, l)| [Some(f), Some(l)]);
+        for id in links.into_iter().chain(children).flatten() {
+            if id.to_index() >= vec.len() {
+                return Err(format!(
+                    "node {} links to out of bounds node {}",
+                    index,
+                    id.to_index()
+                ));
+            }
+        }
+    }
+
+    let mut child_count = 0;
+    for (index, n) in vec.iter().enumerate() {
+        let id = unsafe { NodeId::from_index(index) };
+        let asymmetric = |other: NodeId| {
+            Err(format!(
+                "asymmetric links between node {} and node {}",
+                index,
+                other.to_index()
+            ))
+        };
+
+        if let Some(prev_sibling_id) = n.prev_sibling {
+            let prev_sibling = node(prev_sibling_id);
+            if prev_sibling.next_sibling != Some(id) || prev_sibling.parent != n.parent {
+                return asymmetric(prev_sibling_id);
+            }
+        }
+        if let Some(next_sibling_id) = n.next_sibling {
+            let next_sibling = node(next_sibling_id);
+            if next_sibling.prev_sibling != Some(id) || next_sibling.parent != n.parent {
+                return asymmetric(next_sibling_id);
+            }
+        }
+
+        match n.parent {
+            None if n.prev_sibling.is_some() || n.next_sibling.is_some() => {
+                return Err(format!("orphan node {} has siblings", index));
+            }
+            None => {}
+            Some(parent_id) => {
+                child_count += 1;
+                let (first_child_id, last_child_id) = match node(parent_id).children {
+                    Some(ids) => ids,
+                    None => return asymmetric(parent_id),
+                };
+                if (n.prev_sibling.is_none() && first_child_id != id)
+                    || (n.next_sibling.is_none() && last_child_id != id)
+                {
+                    return asymmetric(parent_id);
+                }
+            }
+        }
+
+        if let Some((first_child_id, last_child_id)) = n.children {
+            let first_child = node(first_child_id);
+            let last_child = node(last_child_id);
+            if first_child.parent != Some(id) || first_child.prev_sibling.is_some() {
+                return asymmetric(first_child_id);
+            }
+            if last_child.parent != Some(id) || last_child.next_sibling.is_some() {
+                return asymmetric(last_child_id);
+            }
+        }
+    }
+
+    // Sibling chains starting at a first child cannot loop, as links are
+    // symmetric. Children missing from these chains are in a sibling cycle.
+    let chained_count: usize = vec
+        .iter()
+        .filter_map(|n| n.children)
+        .map(|(first_child_id, _)| {
+            std::iter::successors(Some(first_child_id), |&id| node(id).next_sibling).count()
+        })
+        .sum();
+    if chained_count != child_count {
+        return Err("cycle in sibling links".to_owned());
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        Visiting,
+        Acyclic,
+    }
+    let mut states = vec![State::Unvisited; vec.len()];
+    let mut path = Vec::new();
+    for index in 0..vec.len() {
+        let mut id = Some(unsafe { NodeId::from_index(index) });
+        while let Some(ancestor_id) = id {
+            match states[ancestor_id.to_index()] {
+                State::Acyclic => break,
+                State::Visiting => {
+                    return Err(format!("cycle in parent links of node {}", index));
+                }
+                State::Unvisited => {
+                    states[ancestor_id.to_index()] = State::Visiting;
+                    path.push(ancestor_id);
+                    id = node(ancestor_id).parent;
+                }
+            }
+        }
+        for id in path.drain(..) {
+            states[id.to_index()] = State::Acyclic;
+        }
+    }
+
+    Ok(())
+}
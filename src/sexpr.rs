@@ -0,0 +1,239 @@
+//! S-expression format.
+//!
+//! # Examples
+//!
+//! ```
+//! # use ego_tree::tree;
+//! use ego_tree::Tree;
+//!
+//! let tree = Tree::from_sexpr(r#"(+ 1 (* 2 "x y")) ; comment"#, |atom| {
+//!     atom.parse::<String>()
+//! })
+//! .unwrap();
+//! assert_eq!(
+//!     format!("{:#?}", tree),
+//!     format!("{:#?}", tree!("+" => { "1", "*" => { "2", "x y" } })),
+//! );
+//! assert_eq!(
+//!     tree.to_sexpr(|node| node.value().clone()),
+//!     r#"(+ 1 (* 2 "x y"))"#,
+//! );
+//! ```
+
+use std::fmt::Display;
+
+use crate::iter::Edge;
+use crate::parse::{line_at, ParseError};
+use crate::{NodeRef, Tree};
+
+impl<T> Tree<T> {
+    /// Returns the tree as an S-expression, with atoms from `label`.
+    ///
+    /// Leaves are written as atoms and other nodes as lists of their atom
+    /// followed by their children. Atoms are quoted if needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ego_tree::tree;
+    /// let tree = tree!("+" => { "1", "*" => { "2", "x y" } });
+    /// let sexpr = tree.to_sexpr(|node| node.value().to_string());
+    /// assert_eq!(sexpr, r#"(+ 1 (* 2 "x y"))"#);
+    /// ```
+    pub fn to_sexpr<F>(&self, mut label: F) -> String
+    where
+        F: FnMut(NodeRef<T>) -> String,
+    {
+        let mut sexpr = String::new();
+        for edge in self.root().traverse() {
+            match edge {
+                Edge::Open(node) => {
+                    if node != self.root() {
+                        sexpr.push(' ');
+                    }
+                    if node.has_children() {
+                        sexpr.push('(');
+                    }
+                    write_atom(&mut sexpr, &label(node));
+                }
+                Edge::Close(node) if node.has_children() => sexpr.push(')'),
+                Edge::Close(_) => {}
+            }
+        }
+        sexpr
+    }
+
+    /// Parses a tree from an S-expression, with values from `parse_atom`.
+    ///
+    /// A list `(head child ...)` is a node with the value of its head atom
+    /// and the given children, and an atom alone is a leaf. Atoms are either
+    /// bare or quoted with `"`, in which case `\"`, `\\`, `\n`, `\r` and `\t`
+    /// escapes are recognized. Comments run from `;` to the end of the line.
+    ///
+    /// Parsing is iterative, so deeply nested expressions are supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression is malformed, if a list is empty
+    /// or starts with a list, or if `parse_atom` fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ego_tree::{tree, Tree};
+    /// let tree = Tree::from_sexpr("(+ 1 (* 2 3))", |atom| atom.parse::<String>()).unwrap();
+    /// assert_eq!(
+    ///     format!("{:#?}", tree),
+    ///     format!("{:#?}", tree!("+" => { "1", "*" => { "2", "3" } })),
+    /// );
+    /// ```
+    pub fn from_sexpr<F, E>(s: &str, mut parse_atom: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(&str) -> Result<T, E>,
+        E: Display,
+    {
+        let mut reader = Reader { s, pos: 0 };
+        let mut tree: Option<Tree<T>> = None;
+        let mut parent_ids = Vec::new();
+
+        loop {
+            reader.skip_whitespace();
+            if reader.rest().is_empty() {
+                return match tree {
+                    Some(tree) if parent_ids.is_empty() => Ok(tree),
+                    Some(_) => Err(reader.error("expected `)`")),
+                    None => Err(reader.error("expected an expression")),
+                };
+            }
+            if tree.is_some() && parent_ids.is_empty() {
+                return Err(reader.error("unexpected input after the expression"));
+            }
+
+            if reader.eat(')') {
+                if parent_ids.pop().is_none() {
+                    return Err(reader.error("unmatched `)`"));
+                }
+                continue;
+            }
+
+            let list = reader.eat('(');
+            if list {
+                reader.skip_whitespace();
+                if reader.rest().starts_with(['(', ')']) || reader.rest().is_empty() {
+                    return Err(reader.error("expected an atom at the head of a list"));
+                }
+            }
+
+            let start = reader.pos;
+            let atom = reader.atom()?;
+            let value = parse_atom(&atom).map_err(|err| {
+                reader.error_at(start, format!("invalid atom {:?}: {}", atom, err))
+            })?;
+            let id = match (&mut tree, parent_ids.last()) {
+                (Some(tree), Some(&parent_id)) => tree.get_mut(parent_id).unwrap().append(value).id,
+                _ => tree.insert(Tree::new(value)).root().id,
+            };
+            if list {
+                parent_ids.push(id);
+            }
+        }
+    }
+}
+
+/// Writes an atom, quoted if needed.
+fn write_atom(sexpr: &mut String, atom: &str) {
+    let bare = !atom.is_empty()
+        && !atom
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '(' | ')' | '"' | ';' | '\\'));
+    if bare {
+        sexpr.push_str(atom);
+        return;
+    }
+
+    sexpr.push('"');
+    for c in atom.chars() {
+        match c {
+            '"' => sexpr.push_str("\\\""),
+            '\\' => sexpr.push_str("\\\\"),
+            '\n' => sexpr.push_str("\\n"),
+            '\r' => sexpr.push_str("\\r"),
+            '\t' => sexpr.push_str("\\t"),
+            c => sexpr.push(c),
+        }
+    }
+    sexpr.push('"');
+}
+
+/// S-expression reader.
+struct Reader<'s> {
+    s: &'s str,
+    pos: usize,
+}
+
+impl<'s> Reader<'s> {
+    fn rest(&self) -> &'s str {
+        &self.s[self.pos..]
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> ParseError {
+        ParseError::new(line_at(self.s, pos), message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if !trimmed.starts_with(';') {
+                return;
+            }
+            self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.rest().starts_with(c);
+        if found {
+            self.pos += c.len_utf8();
+        }
+        found
+    }
+
+    /// Reads a bare or quoted atom.
+    fn atom(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        let rest = self.rest();
+        if !rest.starts_with('"') {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '"' | ';'))
+                .unwrap_or(rest.len());
+            self.pos += end;
+            return Ok(rest[..end].to_owned());
+        }
+
+        let mut atom = String::new();
+        let mut chars = rest.char_indices().skip(1);
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(atom);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => atom.push('\n'),
+                    Some((_, 'r')) => atom.push('\r'),
+                    Some((_, 't')) => atom.push('\t'),
+                    Some((_, c @ ('"' | '\\'))) => atom.push(c),
+                    _ => return Err(self.error("invalid escape sequence")),
+                },
+                c => atom.push(c),
+            }
+        }
+        Err(self.error_at(start, "unterminated string"))
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::iter::Edge;
+use crate::{NodeId, NodeMap, NodeRef, Tree};
+
+impl<T: PartialEq> Tree<T> {
+    /// Returns true if the subtrees of the roots of both trees have the same
+    /// shape and values.
+    ///
+    /// Unlike `==`, node IDs and orphans are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ego_tree::{tree, Tree};
+    /// let a = tree!('a' => { 'b', 'c' });
+    /// let mut b = Tree::new('a');
+    /// b.orphan('x');
+    /// b.root_mut().append('c');
+    /// b.root_mut().prepend('b');
+    /// assert!(a != b);
+    /// assert!(a.structural_eq(&b));
+    /// ```
+    pub fn structural_eq(&self, other: &Self) -> bool {
+        self.root().subtree_eq(&other.root())
+    }
+}
+
+impl<'a, T: PartialEq + 'a> NodeRef<'a, T> {
+    /// Returns true if the subtrees of both nodes have the same shape and
+    /// values.
+    ///
+    /// The nodes can be in different trees.
+    pub fn subtree_eq(&self, other: &NodeRef<T>) -> bool {
+        let mut edges = self.traverse();
+        let mut other_edges = other.traverse();
+        loop {
+            match (edges.next(), other_edges.next()) {
+                (Some(Edge::Open(a)), Some(Edge::Open(b))) if a.value() == b.value() => {}
+                (Some(Edge::Close(_)), Some(Edge::Close(_))) => {}
+                (None, None) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+
+/// Hashes the shape and values of a subtree, consistently with
+/// [`NodeRef::subtree_eq`].
+fn hash_subtree<T: Hash, H: Hasher>(node: NodeRef<T>, state: &mut H) {
+    for edge in node.traverse() {
+        match edge {
+            Edge::Open(node) => {
+                state.write_u8(1);
+                node.value().hash(state);
+            }
+            Edge::Close(_) => state.write_u8(0),
+        }
+    }
+}
+
+/// Tree wrapper whose `==` and `Hash` compare only the subtree of the root,
+/// like [`Tree::structural_eq`].
+///
+/// # Examples
+///
+/// ```
+/// # use ego_tree::tree;
+/// use std::collections::HashSet;
+///
+/// use ego_tree::StructuralHash;
+///
+/// let mut a = tree!('a' => { 'b' });
+/// let b = a.root().first_child().unwrap().id();
+/// a.get_mut(b).unwrap().detach();
+/// a.root_mut().append('b');
+///
+/// let mut set = HashSet::new();
+/// set.insert(StructuralHash(a));
+/// assert!(set.contains(&StructuralHash(tree!('a' => { 'b' }))));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StructuralHash<T>(pub Tree<T>);
+
+impl<T: PartialEq> PartialEq for StructuralHash<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.structural_eq(&other.0)
+    }
+}
+
+impl<T: Eq> Eq for StructuralHash<T> {}
+
+impl<T: Hash> Hash for StructuralHash<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_subtree(self.0.root(), state);
+    }
+}
+
+impl<'a, T: Hash + 'a> NodeRef<'a, T> {
+    /// Returns a Merkle hash of the subtree of this node, with hashers of
+    /// type `H`.
+    ///
+    /// The hash of a node combines its value and the hashes of its children,
+    /// so equal subtrees have equal hashes, wherever they are. It is the same
+    /// as the hash of this node in [`Tree::subtree_hashes_with`].
+    pub fn subtree_hash<H: Hasher + Default>(&self) -> u64 {
+        let mut hash = 0;
+        merkle_hashes::<T, H, _>(*self, |_, node_hash| hash = node_hash);
+        hash
+    }
+}
+
+impl<T: Hash> Tree<T> {
+    /// Returns the Merkle hashes of the subtrees of all the nodes of the
+    /// subtree of the root, computed with `DefaultHasher`.
+    ///
+    /// Hashes are computed in a single post-order pass. Orphans are not
+    /// included. As `DefaultHasher` may change between Rust releases, the
+    /// hashes should not be persisted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use ego_tree::tree;
+    /// let tree = tree!('r' => { 'a' => { 'b' }, 'a' => { 'b' }, 'a' });
+    /// let hashes = tree.subtree_hashes();
+    /// let mut children = tree.root().children().map(|node| hashes[node.id()]);
+    /// let (first, second, third) = (children.next(), children.next(), children.next());
+    /// assert_eq!(first, second);
+    /// assert_ne!(first, third);
+    /// ```
+    pub fn subtree_hashes(&self) -> NodeMap<u64> {
+        self.subtree_hashes_with::<DefaultHasher>()
+    }
+
+    /// Returns the Merkle hashes of the subtrees of all the nodes of the
+    /// subtree of the root, with hashers of type `H`.
+    ///
+    /// See [`NodeRef::subtree_hash`].
+    pub fn subtree_hashes_with<H: Hasher + Default>(&self) -> NodeMap<u64> {
+        let mut hashes = NodeMap::with_capacity(self.vec.len());
+        merkle_hashes::<T, H, _>(self.root(), |id, hash| {
+            hashes.insert(id, hash);
+        });
+        hashes
+    }
+}
+
+/// Calls `f` with the Merkle hash of each node of a subtree, in post-order.
+fn merkle_hashes<T, H, F>(root: NodeRef<T>, mut f: F)
+where
+    T: Hash,
+    H: Hasher + Default,
+    F: FnMut(NodeId, u64),
+{
+    // Hashes of the children of the open nodes, and where the children of
+    // each open node start.
+    let mut hashes = Vec::new();
+    let mut starts = Vec::new();
+    for edge in root.traverse() {
+        match edge {
+            Edge::Open(_) => starts.push(hashes.len()),
+            Edge::Close(node) => {
+                let start = starts.pop().unwrap();
+                let mut hasher = H::default();
+                node.value().hash(&mut hasher);
+                hasher.write_usize(hashes.len() - start);
+                for &hash in &hashes[start..] {
+                    hasher.write_u64(hash);
+                }
+                let hash = hasher.finish();
+                hashes.truncate(start);
+                hashes.push(hash);
+                f(node.id, hash);
+            }
+        }
+    }
+}
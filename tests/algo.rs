@@ -0,0 +1,173 @@
+use ego_tree::algo::{
+    canonical_ranks, edit_distance, edit_mapping, is_isomorphic_unordered, unit_cost, EditMapping,
+    Operation,
+};
+use ego_tree::{tree, NodeRef, Tree};
+
+fn values<'a>(pairs: &[(NodeRef<'a, char>, NodeRef<'a, char>)]) -> Vec<(char, char)> {
+    pairs
+        .iter()
+        .map(|(a, b)| (*a.value(), *b.value()))
+        .collect()
+}
+
+#[test]
+fn edit_distance_unit() {
+    let a = tree!('f' => { 'd' => { 'a', 'c' => { 'b' } }, 'e' });
+    let b = tree!('f' => { 'c' => { 'd' => { 'a', 'b' } }, 'e' });
+    assert_eq!(2, edit_distance(a.root(), b.root(), unit_cost));
+    assert_eq!(2, edit_distance(b.root(), a.root(), unit_cost));
+    assert_eq!(0, edit_distance(a.root(), a.root(), unit_cost));
+
+    let single = tree!('a');
+    assert_eq!(0, edit_distance(single.root(), single.root(), unit_cost));
+    assert_eq!(
+        1,
+        edit_distance(single.root(), tree!('b').root(), unit_cost)
+    );
+    assert_eq!(5, edit_distance(single.root(), a.root(), unit_cost));
+    assert_eq!(5, edit_distance(a.root(), single.root(), unit_cost));
+}
+
+#[test]
+fn edit_distance_subtrees() {
+    let a = tree!('r' => { 'x' => { 'a', 'b' }, 'y' => { 'a', 'b', 'c' } });
+    let x = a.root().first_child().unwrap();
+    let y = a.root().last_child().unwrap();
+    assert_eq!(2, edit_distance(x, y, unit_cost));
+    assert_eq!(3, edit_distance(x.first_child().unwrap(), y, unit_cost));
+}
+
+#[test]
+fn edit_distance_costs() {
+    let a = tree!("a" => { "b", "c" });
+    let b = tree!("a" => { "x", "c" });
+    let cost = |relabel| {
+        move |operation: Operation<&str>| match operation {
+            Operation::Insert(_) | Operation::Delete(_) => 3,
+            Operation::Relabel(a, b) if a.value() == b.value() => 0,
+            Operation::Relabel(..) => relabel,
+        }
+    };
+    assert_eq!(4, edit_distance(a.root(), b.root(), cost(4)));
+    assert_eq!(6, edit_distance(a.root(), b.root(), cost(10)));
+
+    let weighted = |operation: Operation<&str>| match operation {
+        Operation::Insert(node) | Operation::Delete(node) => node.value().len() as u64,
+        Operation::Relabel(a, b) => (a.value() != b.value()) as u64 * 100,
+    };
+    let a = tree!("root" => { "long", "a" });
+    let b = tree!("root" => { "a" });
+    assert_eq!(4, edit_distance(a.root(), b.root(), weighted));
+}
+
+#[test]
+fn edit_mapping_pairs() {
+    let a = tree!('f' => { 'd' => { 'a', 'c' => { 'b' } }, 'e' });
+    let b = tree!('f' => { 'c' => { 'd' => { 'a', 'b' } }, 'e' });
+    let EditMapping { distance, pairs } = edit_mapping(a.root(), b.root(), unit_cost);
+    assert_eq!(2, distance);
+    assert_eq!(
+        vec![('a', 'a'), ('b', 'b'), ('d', 'd'), ('e', 'e'), ('f', 'f')],
+        values(&pairs)
+    );
+
+    let a = tree!('r' => { 'a', 'b', 'c' });
+    let b = tree!('s' => { 'b', 'x', 'c' });
+    let EditMapping { distance, pairs } = edit_mapping(a.root(), b.root(), unit_cost);
+    assert_eq!(3, distance);
+    let cost: u64 = pairs
+        .iter()
+        .map(|&(a, b)| unit_cost(Operation::Relabel(a, b)))
+        .sum();
+    assert_eq!(distance, cost + (4 - pairs.len() as u64) * 2);
+}
+
+#[test]
+fn edit_mapping_consistent() {
+    let trees = [
+        tree!('a'),
+        tree!('a' => { 'b', 'c' }),
+        tree!('a' => { 'c', 'b' }),
+        tree!('x' => { 'b' => { 'c' => { 'd' } }, 'e' }),
+        tree!('a' => { 'e' => { 'b', 'd' => { 'c' } }, 'f', 'g' }),
+        tree!('a' => { 'd' => { 'c' => { 'b' } } }),
+    ];
+    for a in &trees {
+        for b in &trees {
+            let EditMapping { distance, pairs } = edit_mapping(a.root(), b.root(), unit_cost);
+            assert_eq!(distance, edit_distance(a.root(), b.root(), unit_cost));
+            let n = a.root().descendants().count() + b.root().descendants().count();
+            let relabel: u64 = pairs
+                .iter()
+                .map(|&(a, b)| unit_cost(Operation::Relabel(a, b)))
+                .sum();
+            assert_eq!(distance, relabel + (n - 2 * pairs.len()) as u64);
+        }
+    }
+}
+
+fn normalize<T: Ord>(tree: &mut Tree<T>) {
+    let ranks = canonical_ranks(tree.root());
+    let ids: Vec<_> = tree.root().descendants().map(|node| node.id()).collect();
+    for id in ids {
+        tree.get_mut(id)
+            .unwrap()
+            .sort_children_by_key(|child| ranks[child.id()]);
+    }
+}
+
+#[test]
+fn isomorphic_unordered() {
+    let a = tree!('a' => { 'b' => { 'c', 'd' => { 'e' } }, 'f', 'b' => { 'c' } });
+    let b = tree!('a' => { 'b' => { 'c' }, 'f', 'b' => { 'd' => { 'e' }, 'c' } });
+    assert!(is_isomorphic_unordered(a.root(), b.root()));
+    assert!(is_isomorphic_unordered(a.root(), a.root()));
+
+    // Same multiset of values at each depth, different shapes.
+    let c = tree!('a' => { 'b' => { 'c', 'd' => { 'e' } }, 'f', 'b' => { 'd' } });
+    let d = tree!('a' => { 'b' => { 'c' }, 'b' => { 'd' }, 'f' => { 'c' } });
+    assert!(!is_isomorphic_unordered(a.root(), c.root()));
+    assert!(!is_isomorphic_unordered(a.root(), d.root()));
+
+    // Subtrees of different trees.
+    let x = tree!('x' => { 'b' => { 'd' => { 'e' }, 'c' } });
+    let first = a.root().first_child().unwrap();
+    assert!(is_isomorphic_unordered(
+        first,
+        x.root().first_child().unwrap()
+    ));
+    assert!(!is_isomorphic_unordered(first, x.root()));
+
+    assert!(is_isomorphic_unordered(
+        tree!('a').root(),
+        tree!('a').root()
+    ));
+    assert!(!is_isomorphic_unordered(
+        tree!('a').root(),
+        tree!('b').root()
+    ));
+}
+
+#[test]
+fn canonical_ranks_normalize() {
+    let mut a = tree!('a' => { 'b' => { 'c', 'd' => { 'e' } }, 'f', 'b' => { 'c' } });
+    let mut b = tree!('a' => { 'b' => { 'c' }, 'f', 'b' => { 'd' => { 'e' }, 'c' } });
+    normalize(&mut a);
+    normalize(&mut b);
+
+    // Lower subtrees first, then by value.
+    let expected = tree!('a' => { 'f', 'b' => { 'c' }, 'b' => { 'c', 'd' => { 'e' } } });
+    assert_eq!(format!("{:#?}", a), format!("{:#?}", expected));
+    assert_eq!(format!("{:#?}", b), format!("{:#?}", expected));
+
+    let ranks = canonical_ranks(a.root());
+    assert_eq!(ranks.len(), 8);
+    let leaves: Vec<_> = a
+        .root()
+        .descendants()
+        .filter(|node| *node.value() == 'c')
+        .map(|node| ranks[node.id()])
+        .collect();
+    assert_eq!(leaves[0], leaves[1]);
+}
@@ -0,0 +1,67 @@
+use ego_tree::diagram::PlantUml;
+use ego_tree::{tree, NodeRef};
+
+fn label(node: NodeRef<&str>) -> String {
+    node.value().to_string()
+}
+
+#[test]
+fn to_mermaid() {
+    let tree = tree!("root" => { "a" => { "b" }, "c" });
+    let expected = r#"graph TD
+    n0["root"]
+    n0 --> n1["a"]
+    n1 --> n2["b"]
+    n0 --> n3["c"]
+"#;
+    assert_eq!(tree.root().to_mermaid(label), expected);
+}
+
+#[test]
+fn to_mermaid_subtree() {
+    let tree = tree!("root" => { "a" => { "b" }, "c" });
+    let a = tree.root().first_child().unwrap();
+    let expected = "graph TD\n    n1[\"a\"]\n    n1 --> n2[\"b\"]\n";
+    assert_eq!(a.to_mermaid(label), expected);
+}
+
+#[test]
+fn to_mermaid_escape() {
+    let tree = tree!("say \"hi\"\n#1");
+    let expected = "graph TD\n    n0[\"say #quot;hi#quot;<br>#35;1\"]\n";
+    assert_eq!(tree.root().to_mermaid(label), expected);
+}
+
+#[test]
+fn to_plantuml() {
+    let tree = tree!("root" => { "a" => { "b" }, "c" });
+    let expected = "@startmindmap\n* root\n** a\n*** b\n** c\n@endmindmap\n";
+    assert_eq!(tree.root().to_plantuml(PlantUml::MindMap, label), expected);
+
+    let expected = "@startwbs\n* root\n** a\n*** b\n** c\n@endwbs\n";
+    assert_eq!(tree.root().to_plantuml(PlantUml::Wbs, label), expected);
+}
+
+#[test]
+fn to_plantuml_multi_line() {
+    let tree = tree!("root" => { "two\nlines" });
+    let expected = "@startmindmap\n* root\n**:two\nlines;\n@endmindmap\n";
+    assert_eq!(tree.root().to_plantuml(PlantUml::MindMap, label), expected);
+}
+
+#[test]
+fn same_order_as_display() {
+    let tree = tree!("root" => { "a" => { "b", "c" }, "d" => { "e" } });
+    let display: Vec<_> = format!("{tree}")
+        .lines()
+        .map(|line| line.trim_start_matches(|c| "│├└─ ".contains(c)).to_owned())
+        .collect();
+    let plantuml: Vec<_> = tree
+        .root()
+        .to_plantuml(PlantUml::Wbs, label)
+        .lines()
+        .filter(|line| !line.starts_with('@'))
+        .map(|line| line.trim_start_matches(['*', ' ']).to_owned())
+        .collect();
+    assert_eq!(display, plantuml);
+}
@@ -0,0 +1,327 @@
+use ego_tree::diff::Edit;
+use ego_tree::{diff, diff_by_key, tree};
+
+#[test]
+fn diff_equal() {
+    let tree = tree!('a' => { 'b' => { 'c' }, 'd' });
+    assert!(diff(&tree, &tree.clone()).is_empty());
+    assert!(diff_by_key(&tree, &tree.clone(), |node| *node.value()).is_empty());
+}
+
+#[test]
+fn diff_by_position() {
+    let a = tree!('a' => { 'b' => { 'c' }, 'd', 'e' });
+    let b = tree!('x' => { 'b' => { 'c', 'f' }, 'e' });
+    let root = a.root().id();
+    let b_id = a.root().first_child().unwrap().id();
+    let d = a.root().children().nth(1).unwrap().id();
+    let e = a.root().last_child().unwrap().id();
+
+    let edits = diff(&a, &b);
+    assert_eq!(4, edits.len());
+    assert_eq!(
+        Edit::Update {
+            id: root,
+            value: 'x'
+        },
+        edits[0]
+    );
+    assert_eq!(Edit::Delete { id: e }, edits[1]);
+    match edits[2] {
+        Edit::Insert {
+            parent,
+            index,
+            value,
+            ..
+        } => assert_eq!((b_id, 1, 'f'), (parent, index, value)),
+        ref edit => panic!("unexpected edit {:?}", edit),
+    }
+    assert_eq!(Edit::Update { id: d, value: 'e' }, edits[3]);
+}
+
+#[test]
+fn diff_delete_sibling() {
+    let a = tree!("r" => { "x", "y", "z" });
+    let b = tree!("r" => { "y", "z" });
+    let x = a.root().first_child().unwrap().id();
+
+    assert_eq!(
+        vec![Edit::Delete { id: x }],
+        diff_by_key(&a, &b, |node| *node.value())
+    );
+    assert_eq!(
+        "  r\n  ├── y\n  ├── z\n- └── x\n",
+        a.display_diff(&diff_by_key(&a, &b, |node| *node.value()))
+            .unwrap()
+            .to_string()
+    );
+
+    let a = tree!('r' => { 'x', 'y' });
+    let b = tree!('r' => { 'x' });
+    let y = a.root().last_child().unwrap().id();
+    assert_eq!(vec![Edit::Delete { id: y }], diff(&a, &b));
+}
+
+#[test]
+fn diff_by_key_moves() {
+    let a = tree!('r' => { 'a' => { 'b' }, 'c' });
+    let b = tree!('r' => { 'c' => { 'b' }, 'a' });
+    let b_id = a.root().first_child().unwrap().first_child().unwrap().id();
+    let c_id = a.root().last_child().unwrap().id();
+
+    let edits = diff_by_key(&a, &b, |node| *node.value());
+    assert_eq!(
+        vec![
+            Edit::Move {
+                id: c_id,
+                parent: a.root().id(),
+                index: 0
+            },
+            Edit::Move {
+                id: b_id,
+                parent: c_id,
+                index: 0
+            },
+        ],
+        edits
+    );
+}
+
+#[test]
+fn diff_by_key_swap_parent_and_child() {
+    let a = tree!('r' => { 'x' => { 'y' => { 'z' } } });
+    let b = tree!('r' => { 'y' => { 'x' => { 'z' } } });
+    let x = a.root().first_child().unwrap();
+    let y = x.first_child().unwrap();
+    let z = y.first_child().unwrap();
+
+    let edits = diff_by_key(&a, &b, |node| *node.value());
+    assert_eq!(
+        vec![
+            Edit::Move {
+                id: y.id(),
+                parent: a.root().id(),
+                index: 0
+            },
+            Edit::Move {
+                id: x.id(),
+                parent: y.id(),
+                index: 0
+            },
+            Edit::Move {
+                id: z.id(),
+                parent: x.id(),
+                index: 0
+            },
+        ],
+        edits
+    );
+}
+
+#[test]
+fn diff_by_key_duplicate_keys() {
+    let a = tree!(0 => { 1, 1, 2 });
+    let b = tree!(0 => { 1, 2, 1, 1 });
+    let edits = diff_by_key(&a, &b, |node| *node.value());
+    assert_eq!(2, edits.len());
+    assert!(matches!(edits[0], Edit::Move { index: 1, .. }));
+    assert!(matches!(
+        edits[1],
+        Edit::Insert {
+            index: 3,
+            value: 1,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn display_edit() {
+    let tree = tree!("a" => { "b" });
+    let b = tree.root().first_child().unwrap().id();
+    let edit = Edit::Update { id: b, value: "c" };
+    assert_eq!(format!("update {:?}: c", b), edit.to_string());
+    assert_eq!("update NodeId(2): c", edit.to_string());
+}
+
+#[test]
+fn display_diff() {
+    let a = tree!("root" => { "a" => { "x", "y" }, "b" => { "c" }, "d" });
+    let b = tree!("root" => { "b" => { "c", "a" => { "y" } }, "e", "d" });
+    let edits = diff_by_key(&a, &b, |node| *node.value());
+    let expected = "  root
+> ├── b
+  │   ├── c
+> │   └── a
+  │       ├── y
+- │       └── x
++ ├── e
+  └── d
+";
+    assert_eq!(expected, a.display_diff(&edits).unwrap().to_string());
+}
+
+#[test]
+fn display_diff_invalid() {
+    use ego_tree::diff::{PatchError, PatchErrorReason};
+
+    let a = tree!(1 => { 2, 3 });
+    let edits = diff(&a, &tree!(1 => { 3 }));
+    let tree = tree!(1);
+    let id = a.root().last_child().unwrap().id();
+    assert_eq!(
+        PatchError {
+            edit: 0,
+            reason: PatchErrorReason::InvalidId(id)
+        },
+        tree.display_diff(&edits).unwrap_err()
+    );
+}
+
+#[test]
+fn display_diff_update() {
+    let a = tree!(1 => { 2, 3 => { 4 } });
+    let b = tree!(1 => { 5, 3 });
+    let edits = diff(&a, &b);
+    let expected = "  1\n~ ├── 2 → 5\n  └── 3\n-     └── 4\n";
+    assert_eq!(expected, a.display_diff(&edits).unwrap().to_string());
+}
+
+fn assert_apply<T>(a: &ego_tree::Tree<T>, b: &ego_tree::Tree<T>, edits: &[Edit<T>])
+where
+    T: Clone + std::fmt::Debug,
+{
+    let mut patched = a.clone();
+    patched.apply(edits).unwrap();
+    assert_eq!(format!("{:#?}", b), format!("{:#?}", patched));
+}
+
+#[test]
+fn apply_diff() {
+    let trees = [
+        tree!('a'),
+        tree!('a' => { 'b', 'c' }),
+        tree!('a' => { 'c', 'b' }),
+        tree!('x' => { 'b' => { 'c' => { 'd' } }, 'e' }),
+        tree!('a' => { 'e' => { 'b', 'd' => { 'c' } }, 'f', 'g' }),
+        tree!('a' => { 'd' => { 'c' => { 'b' } } }),
+        tree!('a' => { 'g', 'f', 'e', 'd', 'c', 'b' }),
+    ];
+    for a in &trees {
+        for b in &trees {
+            assert_apply(a, b, &diff(a, b));
+            assert_apply(a, b, &diff_by_key(a, b, |node| *node.value()));
+        }
+    }
+}
+
+#[test]
+fn apply_orphans() {
+    let mut a = tree!(1 => { 2, 3 });
+    a.orphan(4);
+    let b = tree!(1 => { 3 => { 5 } });
+    let edits = diff_by_key(&a, &b, |node| *node.value());
+    let mut patched = a.clone();
+    patched.apply(&edits).unwrap();
+    assert_eq!(format!("{:#?}", b), format!("{:#?}", patched));
+    assert_eq!(
+        Some(&5),
+        patched.nodes().next_back().map(|node| node.value())
+    );
+}
+
+#[test]
+fn apply_errors() {
+    use ego_tree::diff::{PatchError, PatchErrorReason};
+
+    let tree = tree!('a' => { 'b' => { 'c' }, 'd' });
+    let root = tree.root().id();
+    let b = tree.root().first_child().unwrap().id();
+    let c = tree.get(b).unwrap().first_child().unwrap().id();
+    let d = tree.root().last_child().unwrap().id();
+    let error = |edits: &[Edit<char>]| {
+        let mut patched = tree.clone();
+        let error = patched.apply(edits).unwrap_err();
+        assert_eq!(tree, patched);
+        error
+    };
+
+    let mut other = tree.clone();
+    other.orphan('x');
+    let new_id = other.orphan('y').id();
+    assert_eq!(
+        PatchError {
+            edit: 1,
+            reason: PatchErrorReason::InvalidId(new_id)
+        },
+        error(&[
+            Edit::Update { id: c, value: 'x' },
+            Edit::Delete { id: new_id }
+        ])
+    );
+
+    let next_id = other.nodes().nth(4).unwrap().id();
+    assert_eq!(
+        PatchError {
+            edit: 0,
+            reason: PatchErrorReason::UnexpectedId {
+                id: new_id,
+                expected: next_id
+            }
+        },
+        error(&[Edit::Insert {
+            id: new_id,
+            parent: root,
+            index: 0,
+            value: 'x'
+        }])
+    );
+    assert_eq!(
+        PatchErrorReason::InvalidIndex { index: 2, len: 1 },
+        error(&[Edit::Insert {
+            id: next_id,
+            parent: b,
+            index: 2,
+            value: 'x'
+        }])
+        .reason
+    );
+    assert_eq!(
+        PatchErrorReason::InvalidIndex { index: 2, len: 1 },
+        error(&[Edit::Move {
+            id: d,
+            parent: root,
+            index: 2
+        }])
+        .reason
+    );
+    assert_eq!(
+        PatchErrorReason::Root,
+        error(&[Edit::Delete { id: root }]).reason
+    );
+    assert_eq!(
+        PatchError {
+            edit: 1,
+            reason: PatchErrorReason::Orphan(b)
+        },
+        error(&[Edit::Delete { id: b }, Edit::Delete { id: b }])
+    );
+    assert_eq!(
+        PatchErrorReason::Cycle,
+        error(&[Edit::Move {
+            id: b,
+            parent: c,
+            index: 0
+        }])
+        .reason
+    );
+    assert_eq!(
+        "edit 0: cannot move a node into its own subtree",
+        error(&[Edit::Move {
+            id: b,
+            parent: b,
+            index: 0
+        }])
+        .to_string()
+    );
+}
@@ -0,0 +1,146 @@
+use ego_tree::display::{DisplayOptions, Style};
+use ego_tree::tree;
+
+fn options() -> DisplayOptions {
+    DisplayOptions::default()
+}
+
+#[test]
+fn default_matches_display() {
+    let tree = tree!("root" => { "a" => { "b", "c" }, "d" => { "e" } });
+    assert_eq!(format!("{tree}"), tree.display_with(options()).to_string());
+}
+
+#[test]
+fn styles() {
+    let tree = tree!("root" => { "a" => { "b" }, "c" });
+    let render = |style| {
+        tree.display_with(DisplayOptions { style, ..options() })
+            .to_string()
+    };
+    assert_eq!("root\n├── a\n│   └── b\n└── c\n", render(Style::Unicode));
+    assert_eq!("root\n|-- a\n|   \\-- b\n\\-- c\n", render(Style::Ascii));
+    assert_eq!("root\n├── a\n│   ╰── b\n╰── c\n", render(Style::Rounded));
+    assert_eq!("root\n┣━━ a\n┃   ┗━━ b\n┗━━ c\n", render(Style::Heavy));
+}
+
+#[test]
+fn indent_width() {
+    let tree = tree!("root" => { "a" => { "b" }, "c" });
+    let render = |indent_width| {
+        tree.display_with(DisplayOptions {
+            indent_width,
+            ..options()
+        })
+        .to_string()
+    };
+    assert_eq!("root\n├─ a\n│  └─ b\n└─ c\n", render(3));
+    assert_eq!("root\n├ a\n│ └ b\n└ c\n", render(2));
+    assert_eq!(render(2), render(0));
+    assert_eq!("root\n├────── a\n│       └────── b\n└────── c\n", render(8));
+}
+
+#[test]
+fn hide_root() {
+    let tree = tree!("root" => { "a" => { "b", "c" }, "d" });
+    let options = DisplayOptions {
+        show_root: false,
+        ..options()
+    };
+    assert_eq!(
+        "a\n├── b\n└── c\nd\n",
+        tree.display_with(options).to_string()
+    );
+    assert_eq!("", tree!("root").display_with(options).to_string());
+}
+
+#[test]
+fn max_depth() {
+    let tree = tree!("root" => { "a" => { "b" => { "c" } }, "d" });
+    let render = |max_depth| {
+        tree.display_with(DisplayOptions {
+            max_depth: Some(max_depth),
+            ..options()
+        })
+        .to_string()
+    };
+    assert_eq!("root\n└── …\n", render(0));
+    assert_eq!("root\n├── a\n│   └── …\n└── d\n", render(1));
+    assert_eq!("root\n├── a\n│   └── b\n│       └── …\n└── d\n", render(2));
+    assert_eq!(format!("{tree}"), render(3));
+}
+
+#[test]
+fn max_children() {
+    let tree = tree!("root" => { "a" => { "b", "c", "d" }, "e", "f" });
+    let render = |max_children| {
+        tree.display_with(DisplayOptions {
+            max_children: Some(max_children),
+            ..options()
+        })
+        .to_string()
+    };
+    assert_eq!("root\n└── …\n", render(0));
+    assert_eq!("root\n├── a\n│   ├── b\n│   └── …\n└── …\n", render(1));
+    assert_eq!(
+        "root\n├── a\n│   ├── b\n│   ├── c\n│   └── …\n├── e\n└── …\n",
+        render(2)
+    );
+    assert_eq!(format!("{tree}"), render(3));
+}
+
+#[test]
+fn label() {
+    let tree = tree!(1 => { 2 => { 3 }, 4 });
+    let repr = tree
+        .display_with_label(options(), |node| {
+            format!("{} (depth {})", node.value(), node.ancestors().count())
+        })
+        .to_string();
+    assert_eq!(
+        "1 (depth 0)\n├── 2 (depth 1)\n│   └── 3 (depth 2)\n└── 4 (depth 1)\n",
+        repr
+    );
+}
+
+#[test]
+fn multi_line_labels() {
+    let tree = tree! {
+        "fn f() {\n    1\n}" => {
+            "let x =\n    1;" => { "1" },
+            "x\n+ 1",
+        }
+    };
+    let expected = "\
+fn f() {
+│       1
+│   }
+├── let x =
+│   │       1;
+│   └── 1
+└── x
+        + 1
+";
+    assert_eq!(expected, format!("{tree}"));
+}
+
+#[test]
+fn multi_line_labels_options() {
+    let tree = tree!("root" => { "a\nb" => { "c\nd" }, "e\r\nf" });
+    let options = DisplayOptions {
+        style: Style::Ascii,
+        show_root: false,
+        ..options()
+    };
+    let expected = "a\n|   b\n\\-- c\n        d\ne\n    f\n";
+    assert_eq!(expected, tree.display_with(options).to_string());
+
+    let options = DisplayOptions {
+        max_depth: Some(1),
+        ..options
+    };
+    assert_eq!(
+        "a\n|   b\n\\-- …\ne\n    f\n",
+        tree.display_with(options).to_string()
+    );
+}
@@ -0,0 +1,87 @@
+use ego_tree::dot::DotOptions;
+use ego_tree::tree;
+
+#[test]
+fn to_dot() {
+    let tree = tree!("a" => { "b", "c" => { "d" } });
+    let expected = r#"digraph {
+    0 [label="a"];
+    1 [label="b"];
+    0 -> 1;
+    2 [label="c"];
+    0 -> 2;
+    3 [label="d"];
+    2 -> 3;
+}
+"#;
+    assert_eq!(tree.to_dot(), expected);
+}
+
+#[test]
+fn sibling_edges() {
+    let tree = tree!('a' => { 'b', 'c', 'd' });
+    let options = DotOptions {
+        sibling_edges: true,
+        ..DotOptions::default()
+    };
+    let mut dot = String::new();
+    tree.write_dot(&mut dot, &options).unwrap();
+    let expected = r#"digraph {
+    0 [label="a"];
+    1 [label="b"];
+    0 -> 1;
+    1 -> 2 [style=dashed, constraint=false];
+    2 [label="c"];
+    0 -> 2;
+    2 -> 3 [style=dashed, constraint=false];
+    3 [label="d"];
+    0 -> 3;
+}
+"#;
+    assert_eq!(dot, expected);
+}
+
+#[test]
+fn orphans() {
+    let mut tree = tree!('a' => { 'b' });
+    tree.orphan('x').append('y');
+    let options = DotOptions {
+        orphans: true,
+        ..DotOptions::default()
+    };
+    let mut dot = String::new();
+    tree.write_dot(&mut dot, &options).unwrap();
+    let expected = r#"digraph {
+    0 [label="a"];
+    1 [label="b"];
+    0 -> 1;
+    subgraph cluster_2 {
+        2 [label="x"];
+        3 [label="y"];
+        2 -> 3;
+    }
+}
+"#;
+    assert_eq!(dot, expected);
+    assert!(!tree.to_dot().contains("cluster"));
+}
+
+#[test]
+fn subtree_with_label() {
+    let tree = tree!(1 => { 2 => { 3, 4 }, 5 });
+    let two = tree.root().first_child().unwrap();
+    let mut dot = String::new();
+    two.write_dot_with(&mut dot, &DotOptions::default(), |node| {
+        format!("\"{}\"\n{} children", node.value(), node.children().count())
+    })
+    .unwrap();
+    let expected = r#"digraph {
+    1 [label="\"2\"\n2 children"];
+    2 [label="\"3\"\n0 children"];
+    1 -> 2;
+    3 [label="\"4\"\n0 children"];
+    1 -> 3;
+}
+"#;
+    assert_eq!(dot, expected);
+}
@@ -0,0 +1,74 @@
+use ego_tree::{tree, Tree};
+
+fn spaces(line: &str) -> (usize, String) {
+    let value = line.trim_start();
+    (line.len() - value.len(), value.to_owned())
+}
+
+#[test]
+fn from_indented_lines() {
+    let outline = "
+root
+    a
+        b
+        c
+            d
+    e
+
+    f
+        g
+";
+    let tree = Tree::from_indented_lines(outline.lines(), spaces).unwrap();
+    let expected = tree! {
+        "root" => {
+            "a" => { "b", "c" => { "d" } },
+            "e",
+            "f" => { "g" },
+        }
+    };
+    assert_eq!(format!("{:#?}", expected), format!("{:#?}", tree));
+}
+
+#[test]
+fn from_indented_lines_uneven() {
+    let lines = vec!["root", "  a", "     b", "  c", " \t", "\t\t\td"];
+    let tree = Tree::from_indented_lines(lines, spaces).unwrap();
+    let expected = tree!("root" => { "a" => { "b" }, "c" => { "d" } });
+    assert_eq!(format!("{:#?}", expected), format!("{:#?}", tree));
+}
+
+#[test]
+fn from_indented_lines_errors() {
+    let line = |s: &str| {
+        Tree::from_indented_lines(s.lines(), spaces)
+            .unwrap_err()
+            .line()
+    };
+
+    // Empty.
+    assert_eq!(1, line("\n  \n"));
+    // Multiple roots.
+    assert_eq!(3, line("root\n  a\nother"));
+    assert_eq!(3, line("  root\n    a\n b"));
+    // Dedent to no outer level.
+    assert_eq!(4, line("root\n    a\n        b\n      c"));
+    assert_eq!(
+        "line 3: indentation 1 does not match any outer indentation, expected 2",
+        Tree::from_indented_lines("root\n  a\n b".lines(), spaces)
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+fn to_indented() {
+    let tree = tree!("root" => { "a" => { "b" }, "c" });
+    assert_eq!("root\n  a\n    b\n  c\n", tree.to_indented("  "));
+    assert_eq!("root\n\ta\n\t\tb\n\tc\n", tree.to_indented("\t"));
+
+    let repr = tree.to_indented("- ");
+    assert_eq!("root\n- a\n- - b\n- c\n", repr);
+
+    let parsed = Tree::from_indented_lines(tree.to_indented("  ").lines(), spaces).unwrap();
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", parsed));
+}
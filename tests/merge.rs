@@ -0,0 +1,177 @@
+use ego_tree::merge::{Conflict, ConflictKind, Conflicts};
+use ego_tree::{merge3, tree, NodeId, Tree};
+
+fn merge(
+    base: &Tree<&'static str>,
+    ours: &Tree<&'static str>,
+    theirs: &Tree<&'static str>,
+) -> Result<Tree<&'static str>, Conflicts> {
+    merge3(base, ours, theirs, |node| {
+        node.value().split(':').next().unwrap()
+    })
+}
+
+fn id(tree: &Tree<&str>, value: &str) -> NodeId {
+    tree.root()
+        .descendants()
+        .find(|node| *node.value() == value)
+        .unwrap()
+        .id()
+}
+
+fn kinds(conflicts: &Conflicts) -> Vec<ConflictKind> {
+    conflicts.0.iter().map(|conflict| conflict.kind).collect()
+}
+
+#[test]
+fn merge3_one_side() {
+    let base = tree!("r" => { "a" => { "b", "c" }, "d" });
+    let changed = tree!("r:1" => { "d" => { "c:2" }, "e", "a" });
+    let merged = merge(&base, &changed, &base).unwrap();
+    assert_eq!(format!("{:#?}", merged), format!("{:#?}", changed));
+    let merged = merge(&base, &base, &changed).unwrap();
+    assert_eq!(format!("{:#?}", merged), format!("{:#?}", changed));
+    let merged = merge(&base, &base, &base).unwrap();
+    assert_eq!(format!("{:#?}", merged), format!("{:#?}", base));
+}
+
+#[test]
+fn merge3_both_sides() {
+    let base = tree!("r" => { "a" => { "b", "c" }, "d", "e" });
+    // Updates `b`, moves `c` under `d`, inserts `f` and deletes `e`.
+    let ours = tree!("r" => { "a" => { "b:1" }, "d" => { "c" }, "f" });
+    // Updates `d`, inserts `g` under `a` and `h` at the front, and moves `e`
+    // first.
+    let theirs = tree!("r" => { "h", "e", "a" => { "b", "g", "c" }, "d:2" });
+    let merged = merge(&base, &ours, &theirs).unwrap();
+    let expected = tree!("r" => { "h", "a" => { "b:1", "g" }, "d:2" => { "c" }, "f" });
+    assert_eq!(format!("{:#?}", merged), format!("{:#?}", expected));
+}
+
+#[test]
+fn merge3_same_changes() {
+    let base = tree!("r" => { "a", "b" });
+    let ours = tree!("r" => { "b" => { "c:1" }, "a:2" });
+    let merged = merge(&base, &ours, &ours.clone()).unwrap();
+    assert_eq!(format!("{:#?}", merged), format!("{:#?}", ours));
+
+    // Both sides insert the same node in different places.
+    let ours = tree!("r" => { "a" => { "c" }, "b" });
+    let theirs = tree!("r" => { "a", "b" => { "c" } });
+    let conflicts = merge(&base, &ours, &theirs).unwrap_err();
+    assert_eq!(
+        conflicts.0,
+        [Conflict {
+            kind: ConflictKind::Move,
+            base: None,
+            ours: Some(id(&ours, "c")),
+            theirs: Some(id(&theirs, "c")),
+        }]
+    );
+}
+
+#[test]
+fn merge3_value_conflict() {
+    let base = tree!("r" => { "a", "b" });
+    let ours = tree!("r" => { "a:1", "b" });
+    let theirs = tree!("r" => { "a:2", "b:2" });
+    let conflicts = merge(&base, &ours, &theirs).unwrap_err();
+    assert_eq!(
+        conflicts.0,
+        [Conflict {
+            kind: ConflictKind::Value,
+            base: Some(id(&base, "a")),
+            ours: Some(id(&ours, "a:1")),
+            theirs: Some(id(&theirs, "a:2")),
+        }]
+    );
+}
+
+#[test]
+fn merge3_move_conflict() {
+    let base = tree!("r" => { "a", "b", "c" });
+    let ours = tree!("r" => { "a" => { "c" }, "b" });
+    let theirs = tree!("r" => { "a", "b" => { "c" } });
+    let conflicts = merge(&base, &ours, &theirs).unwrap_err();
+    assert_eq!(kinds(&conflicts), [ConflictKind::Move]);
+    assert_eq!(conflicts.0[0].base, Some(id(&base, "c")));
+}
+
+#[test]
+fn merge3_delete_conflicts() {
+    let base = tree!("r" => { "a" => { "b" }, "c" });
+
+    // Deleting a subtree conflicts with updating a node of it.
+    let ours = tree!("r" => { "c" });
+    let theirs = tree!("r" => { "a" => { "b:1" }, "c" });
+    let conflicts = merge(&base, &ours, &theirs).unwrap_err();
+    assert_eq!(
+        conflicts.0,
+        [Conflict {
+            kind: ConflictKind::Delete,
+            base: Some(id(&base, "b")),
+            ours: None,
+            theirs: Some(id(&theirs, "b:1")),
+        }]
+    );
+
+    // And with moving a node of it, or inserting a node into it.
+    let theirs = tree!("r" => { "a" => { "d" }, "c" => { "b" } });
+    let conflicts = merge(&base, &ours, &theirs).unwrap_err();
+    assert_eq!(
+        kinds(&conflicts),
+        [ConflictKind::Delete, ConflictKind::Delete]
+    );
+    assert_eq!(conflicts.0[0].base, Some(id(&base, "b")));
+    assert_eq!(conflicts.0[1].base, Some(id(&base, "a")));
+
+    // Moving a node out of a deleted subtree on the same side merges.
+    let ours = tree!("r" => { "c" => { "b" } });
+    let theirs = tree!("r" => { "a" => { "b" }, "c:2" });
+    let merged = merge(&base, &ours, &theirs).unwrap();
+    let expected = tree!("r" => { "c:2" => { "b" } });
+    assert_eq!(format!("{:#?}", merged), format!("{:#?}", expected));
+}
+
+#[test]
+fn merge3_order_conflict() {
+    let base = tree!("r" => { "a", "b", "c" });
+    let ours = tree!("r" => { "b", "a", "c" });
+    let theirs = tree!("r" => { "a", "c", "b" });
+    let conflicts = merge(&base, &ours, &theirs).unwrap_err();
+    assert_eq!(
+        conflicts.0,
+        [Conflict {
+            kind: ConflictKind::Order,
+            base: Some(base.root().id()),
+            ours: Some(ours.root().id()),
+            theirs: Some(theirs.root().id()),
+        }]
+    );
+
+    // The same reordering merges.
+    let merged = merge(&base, &ours, &ours.clone()).unwrap();
+    assert_eq!(format!("{:#?}", merged), format!("{:#?}", ours));
+}
+
+#[test]
+fn merge3_cycle_conflict() {
+    let base = tree!("r" => { "a", "b" });
+    let ours = tree!("r" => { "a" => { "b" } });
+    let theirs = tree!("r" => { "b" => { "a" } });
+    let conflicts = merge(&base, &ours, &theirs).unwrap_err();
+    assert_eq!(kinds(&conflicts), [ConflictKind::Cycle]);
+}
+
+#[test]
+fn conflicts_display() {
+    let base = tree!("r" => { "a" });
+    let ours = tree!("r:1" => { "a:1" });
+    let theirs = tree!("r:2");
+    let conflicts = merge(&base, &ours, &theirs).unwrap_err();
+    assert_eq!(
+        conflicts.to_string(),
+        "conflicting values (base NodeId(1), ours NodeId(1), theirs NodeId(1)); \
+         deleted on one side and changed on the other (base NodeId(2), ours NodeId(2))",
+    );
+}
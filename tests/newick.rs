@@ -0,0 +1,123 @@
+use ego_tree::newick::NewickNode;
+use ego_tree::{tree, Tree};
+
+fn node(label: &str, length: Option<f64>) -> NewickNode {
+    NewickNode {
+        label: Some(label.to_owned()).filter(|label| !label.is_empty()),
+        length,
+    }
+}
+
+#[test]
+fn from_newick() {
+    let tree = Tree::from_newick("((A:0.1,B:0.2)C:0.3)D;").unwrap();
+    let expected = tree! {
+        node("D", None) => {
+            node("C", Some(0.3)) => {
+                node("A", Some(0.1)),
+                node("B", Some(0.2)),
+            },
+        }
+    };
+    assert_eq!(format!("{:#?}", expected), format!("{:#?}", tree));
+}
+
+#[test]
+fn from_newick_unlabeled() {
+    let tree = Tree::from_newick("(,(,));").unwrap();
+    let expected = tree! {
+        node("", None) => {
+            node("", None),
+            node("", None) => { node("", None), node("", None) },
+        }
+    };
+    assert_eq!(format!("{:#?}", expected), format!("{:#?}", tree));
+
+    let tree = Tree::from_newick("A;").unwrap();
+    assert_eq!(
+        format!("{:#?}", tree!(node("A", None))),
+        format!("{:#?}", tree)
+    );
+}
+
+#[test]
+fn from_newick_labels() {
+    let tree = Tree::from_newick(
+        "
+        ( 'it''s (quoted)' : 1e-3 [a comment],
+          Homo_sapiens:2 )
+        'root node' : 0.0 ;
+        ",
+    )
+    .unwrap();
+    let expected = tree! {
+        node("root node", Some(0.0)) => {
+            node("it's (quoted)", Some(0.001)),
+            node("Homo sapiens", Some(2.0)),
+        }
+    };
+    assert_eq!(format!("{:#?}", expected), format!("{:#?}", tree));
+
+    let tree = Tree::from_newick("'';").unwrap();
+    assert_eq!(Some(""), tree.root().value().label.as_deref());
+}
+
+#[test]
+fn to_newick() {
+    let newick = "((A:0.1,B:0.2)C:0.3)D;";
+    assert_eq!(newick, Tree::from_newick(newick).unwrap().to_newick());
+    assert_eq!("(,(,));", Tree::from_newick("(,(,));").unwrap().to_newick());
+
+    let tree = tree! {
+        node("root", None) => {
+            node("a b", Some(1.5)),
+            node("it's", None),
+            node("x_y", None),
+        }
+    };
+    let newick = tree.to_newick();
+    assert_eq!("('a b':1.5,'it''s','x_y')root;", newick);
+    let parsed = Tree::from_newick(&newick).unwrap();
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", parsed));
+}
+
+#[test]
+fn from_newick_deep() {
+    let depth = 10_000;
+    let newick = format!("{}A{};", "(".repeat(depth), ")".repeat(depth));
+    let tree = Tree::from_newick(&newick).unwrap();
+    assert_eq!(depth + 1, tree.root().descendants().count());
+    assert_eq!(newick, tree.to_newick());
+}
+
+#[test]
+fn from_newick_many_leaves() {
+    // Lines are only counted to report errors, so this parses in linear time.
+    let leaves = 80_000;
+    let newick = format!("({}A);", "A,".repeat(leaves - 1));
+    let tree = Tree::from_newick(&newick).unwrap();
+    assert_eq!(leaves, tree.root().children().count());
+
+    let newick = format!("({}A;", "A,\n".repeat(leaves - 1));
+    let error = Tree::from_newick(&newick).unwrap_err();
+    assert_eq!(leaves, error.line());
+}
+
+#[test]
+fn from_newick_errors() {
+    let error = |s| Tree::from_newick(s).unwrap_err().to_string();
+
+    assert_eq!("line 1: expected `;`", error(""));
+    assert_eq!("line 1: expected `;`", error("A"));
+    assert_eq!("line 2: expected `)`", error("(A,\nB;"));
+    assert_eq!("line 1: unmatched `)`", error("A);"));
+    assert_eq!("line 1: unexpected `,`", error("A,B;"));
+    assert_eq!("line 1: expected `,` or `)`", error("(A B);"));
+    assert_eq!("line 1: unexpected input after `;`", error("A;B"));
+    assert_eq!("line 1: unterminated label", error("('A,B);"));
+    assert_eq!("line 1: unterminated comment", error("(A[,B);"));
+    assert_eq!(
+        "line 1: invalid branch length \"x\": invalid float literal",
+        error("(A:x);")
+    );
+}
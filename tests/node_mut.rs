@@ -1,7 +1,7 @@
 #[macro_use]
 extern crate ego_tree;
 
-use ego_tree::NodeRef;
+use ego_tree::{NodeRef, RetainMode};
 
 #[test]
 fn value() {
@@ -385,6 +385,403 @@ fn reparent_from_id_prepend() {
     assert_eq!(Some(d), f.prev_sibling());
 }
 
+#[test]
+fn append_sibling_range() {
+    let mut tree = tree!('a' => { 'b' => { 'x' }, 'c', 'd', 'e', 'f' });
+    let ids: Vec<_> = tree.root().children().map(|node| node.id()).collect();
+    tree.get_mut(ids[0])
+        .unwrap()
+        .append_sibling_range(ids[1], ids[3]);
+
+    let expected = tree!('a' => { 'b' => { 'x', 'c', 'd', 'e' }, 'f' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+
+    let b = tree.get(ids[0]).unwrap();
+    assert!(b.children().all(|child| child.parent() == Some(b)));
+    assert_eq!(Some(b), tree.get(ids[4]).unwrap().prev_sibling());
+    assert_eq!(Some(b), tree.get(ids[1]).unwrap().parent());
+    assert_eq!(&'e', b.last_child().unwrap().value());
+}
+
+#[test]
+fn append_sibling_range_outdent() {
+    let mut tree = tree!('a' => { 'b' => { 'c', 'd' }, 'e' });
+    let c_id = tree
+        .root()
+        .first_child()
+        .unwrap()
+        .first_child()
+        .unwrap()
+        .id();
+    let d_id = tree
+        .root()
+        .first_child()
+        .unwrap()
+        .last_child()
+        .unwrap()
+        .id();
+    tree.root_mut().append_sibling_range(c_id, d_id);
+
+    let expected = tree!('a' => { 'b', 'e', 'c', 'd' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+    assert!(!tree.root().first_child().unwrap().has_children());
+}
+
+#[test]
+fn prepend_sibling_range() {
+    let mut tree = tree!('a' => { 'b', 'c', 'd', 'e' => { 'x' } });
+    let ids: Vec<_> = tree.root().children().map(|node| node.id()).collect();
+    tree.get_mut(ids[3])
+        .unwrap()
+        .prepend_sibling_range(ids[0], ids[1]);
+
+    let expected = tree!('a' => { 'd', 'e' => { 'b', 'c', 'x' } });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+    assert_eq!(&'d', tree.root().first_child().unwrap().value());
+}
+
+#[test]
+fn insert_sibling_range() {
+    let mut tree = tree!('a' => { 'b', 'c', 'd', 'e', 'f' });
+    let ids: Vec<_> = tree.root().children().map(|node| node.id()).collect();
+    tree.root_mut().insert_sibling_range(ids[0], ids[1], 2);
+
+    let expected = tree!('a' => { 'd', 'e', 'b', 'c', 'f' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+
+    tree.root_mut().insert_sibling_range(ids[4], ids[4], 0);
+    let expected = tree!('a' => { 'f', 'd', 'e', 'b', 'c' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+
+    let values: Vec<_> = tree
+        .root()
+        .children()
+        .rev()
+        .map(|node| *node.value())
+        .collect();
+    assert_eq!(vec!['c', 'b', 'e', 'd', 'f'], values);
+}
+
+#[test]
+#[should_panic]
+fn append_sibling_range_reversed() {
+    let mut tree = tree!('a' => { 'b', 'c', 'd' });
+    let ids: Vec<_> = tree.root().children().map(|node| node.id()).collect();
+    tree.get_mut(ids[0])
+        .unwrap()
+        .append_sibling_range(ids[2], ids[1]);
+}
+
+#[test]
+#[should_panic]
+fn append_sibling_range_containing_self() {
+    let mut tree = tree!('a' => { 'b', 'c', 'd' });
+    let ids: Vec<_> = tree.root().children().map(|node| node.id()).collect();
+    tree.get_mut(ids[1])
+        .unwrap()
+        .append_sibling_range(ids[0], ids[2]);
+}
+
+#[test]
+#[should_panic]
+fn append_sibling_range_containing_ancestor() {
+    let mut tree = tree!('a' => { 'b' => { 'x' }, 'c' });
+    let ids: Vec<_> = tree.root().children().map(|node| node.id()).collect();
+    let x_id = tree
+        .root()
+        .first_child()
+        .unwrap()
+        .first_child()
+        .unwrap()
+        .id();
+    tree.get_mut(x_id)
+        .unwrap()
+        .append_sibling_range(ids[0], ids[1]);
+}
+
+#[test]
+fn insert_sibling_range_invalid_index() {
+    let mut tree = tree!('a' => { 'b', 'c', 'd' });
+    let ids: Vec<_> = tree.root().children().map(|node| node.id()).collect();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        tree.root_mut().insert_sibling_range(ids[0], ids[1], 3);
+    }));
+    assert!(result.is_err());
+
+    // The range is still linked where it was.
+    let expected = tree!('a' => { 'b', 'c', 'd' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+    assert!(tree
+        .root()
+        .children()
+        .all(|child| child.parent() == Some(tree.root())));
+}
+
+#[test]
+fn unwrap() {
+    let mut tree = tree!('a' => { 'b', 'c' => { 'd', 'e' => { 'f' } }, 'g' });
+    let c_id = tree
+        .root()
+        .first_child()
+        .unwrap()
+        .next_sibling()
+        .unwrap()
+        .id();
+    tree.get_mut(c_id).unwrap().unwrap();
+
+    let c = tree.get(c_id).unwrap();
+    assert_eq!(None, c.parent());
+    assert!(!c.has_children());
+
+    let expected = tree!('a' => { 'b', 'd', 'e' => { 'f' }, 'g' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+
+    let d = tree.root().first_child().unwrap().next_sibling().unwrap();
+    let e = d.next_sibling().unwrap();
+    assert_eq!(Some(tree.root()), d.parent());
+    assert_eq!(Some(tree.root()), e.parent());
+}
+
+#[test]
+fn unwrap_only_child() {
+    let mut tree = tree!('a' => { 'b' => { 'c', 'd' } });
+    let b_id = tree.root().first_child().unwrap().id();
+    tree.get_mut(b_id).unwrap().unwrap();
+
+    let expected = tree!('a' => { 'c', 'd' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+    assert_eq!(&'c', tree.root().first_child().unwrap().value());
+    assert_eq!(&'d', tree.root().last_child().unwrap().value());
+}
+
+#[test]
+fn unwrap_leaf() {
+    let mut tree = tree!('a' => { 'b', 'c' });
+    let b_id = tree.root().first_child().unwrap().id();
+    tree.get_mut(b_id).unwrap().unwrap();
+
+    let expected = tree!('a' => { 'c' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+}
+
+#[test]
+#[should_panic]
+fn unwrap_orphan() {
+    let mut tree = tree!('a' => { 'b' });
+    tree.root_mut().unwrap();
+}
+
+#[test]
+fn wrap() {
+    let mut tree = tree!('a' => { 'b', 'c' => { 'd' }, 'e' });
+    let c_id = tree
+        .root()
+        .first_child()
+        .unwrap()
+        .next_sibling()
+        .unwrap()
+        .id();
+    let x_id = tree.get_mut(c_id).unwrap().wrap('x').id();
+
+    let x = tree.get(x_id).unwrap();
+    assert_eq!(Some(tree.root()), x.parent());
+    assert_eq!(Some(x), tree.get(c_id).unwrap().parent());
+
+    let expected = tree!('a' => { 'b', 'x' => { 'c' => { 'd' } }, 'e' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+}
+
+#[test]
+fn wrap_only_child() {
+    let mut tree = tree!('a' => { 'b' });
+    let b_id = tree.root().first_child().unwrap().id();
+    tree.get_mut(b_id).unwrap().wrap('x');
+
+    let expected = tree!('a' => { 'x' => { 'b' } });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+    assert_eq!(&'x', tree.root().last_child().unwrap().value());
+}
+
+#[test]
+fn wrap_unwrap() {
+    let mut tree = tree!('a' => { 'b', 'c', 'd' });
+    let c_id = tree
+        .root()
+        .first_child()
+        .unwrap()
+        .next_sibling()
+        .unwrap()
+        .id();
+    tree.get_mut(c_id).unwrap().wrap('x').unwrap();
+
+    let expected = tree!('a' => { 'b', 'c', 'd' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+}
+
+#[test]
+fn reverse_children() {
+    let mut tree = tree!('a' => { 'b' => { 'c', 'd' }, 'e', 'f' });
+    tree.root_mut().reverse_children();
+
+    let expected = tree!('a' => { 'f', 'e', 'b' => { 'c', 'd' } });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+
+    let values: Vec<_> = tree
+        .root()
+        .children()
+        .rev()
+        .map(|node| *node.value())
+        .collect();
+    assert_eq!(vec!['b', 'e', 'f'], values);
+}
+
+#[test]
+fn reverse_children_leaf() {
+    let mut tree = tree!('a');
+    tree.root_mut().reverse_children();
+    assert!(!tree.root().has_children());
+}
+
+#[test]
+fn sort_children_by() {
+    let mut tree = tree!('a' => { 'd', 'b' => { 'z', 'y' }, 'c' });
+    tree.root_mut()
+        .sort_children_by(|a, b| a.value().cmp(b.value()));
+
+    let expected = tree!('a' => { 'b' => { 'z', 'y' }, 'c', 'd' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+
+    let values: Vec<_> = tree
+        .root()
+        .children()
+        .rev()
+        .map(|node| *node.value())
+        .collect();
+    assert_eq!(vec!['d', 'c', 'b'], values);
+    assert!(tree
+        .root()
+        .children()
+        .all(|child| child.parent() == Some(tree.root())));
+}
+
+#[test]
+fn sort_children_by_key_stable() {
+    let mut tree = tree!(0 => { 3, 1, 4, 1, 5, 9, 2, 6 });
+    let ids: Vec<_> = tree.root().children().map(|node| node.id()).collect();
+    tree.root_mut()
+        .sort_children_by_key(|node| *node.value() % 2);
+
+    let expected = tree!(0 => { 4, 2, 6, 3, 1, 1, 5, 9 });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+
+    let ones: Vec<_> = tree
+        .root()
+        .children()
+        .filter(|node| *node.value() == 1)
+        .map(|node| node.id())
+        .collect();
+    assert_eq!(ones, [ids[1], ids[3]]);
+}
+
+#[test]
+fn sort_children_leaf() {
+    let mut tree = tree!('a' => { 'b' });
+    tree.root_mut().sort_children_by_key(|node| *node.value());
+    tree.root_mut()
+        .first_child()
+        .unwrap()
+        .sort_children_by_key(|node| *node.value());
+    assert_eq!(
+        format!("{:#?}", tree),
+        format!("{:#?}", tree!('a' => { 'b' }))
+    );
+}
+
+#[test]
+fn reverse_descendants() {
+    let mut tree = tree! {
+        'a' => {
+            'b' => { 'c', 'd' => { 'e', 'f' } },
+            'g',
+            'h' => { 'i' },
+        }
+    };
+    tree.root_mut().reverse_descendants();
+
+    let expected = tree! {
+        'a' => {
+            'h' => { 'i' },
+            'g',
+            'b' => { 'd' => { 'f', 'e' }, 'c' },
+        }
+    };
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+
+    tree.root_mut().reverse_descendants();
+    let expected = tree! {
+        'a' => {
+            'b' => { 'c', 'd' => { 'e', 'f' } },
+            'g',
+            'h' => { 'i' },
+        }
+    };
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+}
+
+#[test]
+fn reverse_descendants_subtree() {
+    let mut tree = tree!('a' => { 'b' => { 'c', 'd' }, 'e' => { 'f', 'g' } });
+    let b_id = tree.root().first_child().unwrap().id();
+    tree.get_mut(b_id).unwrap().reverse_descendants();
+
+    let expected = tree!('a' => { 'b' => { 'd', 'c' }, 'e' => { 'f', 'g' } });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+}
+
+#[test]
+fn retain_descendants_drop_subtree() {
+    let mut tree = tree! {
+        'a' => {
+            'b' => { 'c', 'd' },
+            'x' => { 'e' },
+            'f' => { 'x', 'g' },
+        }
+    };
+    tree.root_mut()
+        .retain_descendants(RetainMode::DropSubtree, |node| *node.value() != 'x');
+
+    let expected = tree!('a' => { 'b' => { 'c', 'd' }, 'f' => { 'g' } });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+}
+
+#[test]
+fn retain_descendants_unwrap() {
+    let mut tree = tree! {
+        'a' => {
+            'x' => { 'b', 'x' => { 'c' }, 'd' },
+            'e' => { 'x' },
+            'x',
+        }
+    };
+    tree.root_mut()
+        .retain_descendants(RetainMode::Unwrap, |node| *node.value() != 'x');
+
+    let expected = tree!('a' => { 'b', 'c', 'd', 'e' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+}
+
+#[test]
+fn retain_descendants_keeps_self() {
+    let mut tree = tree!('a' => { 'b' => { 'c' } });
+    let b_id = tree.root().first_child().unwrap().id();
+    tree.get_mut(b_id)
+        .unwrap()
+        .retain_descendants(RetainMode::DropSubtree, |_| false);
+
+    let expected = tree!('a' => { 'b' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+}
+
 #[test]
 fn into() {
     let mut tree = tree!('a');
@@ -94,3 +94,53 @@ fn neq_tree() {
     let two = one.clone();
     assert_eq!(one.root(), two.root());
 }
+
+#[test]
+fn display() {
+    let tree = tree!("root" => { "a" => { "b", "c" => { "d" } }, "e" });
+    let a = tree.root().first_child().unwrap();
+    assert_eq!("a\n├── b\n└── c\n    └── d\n", format!("{a}"));
+    assert_eq!(format!("{tree}"), format!("{}", tree.root()));
+
+    let e = tree.root().last_child().unwrap();
+    assert_eq!("e\n", format!("{e}"));
+}
+
+#[test]
+fn display_with() {
+    use ego_tree::display::{DisplayOptions, Style};
+
+    let tree = tree!("root" => { "a" => { "b", "c" => { "d" } }, "e" });
+    let a = tree.root().first_child().unwrap();
+    let options = DisplayOptions {
+        style: Style::Ascii,
+        max_depth: Some(1),
+        ..DisplayOptions::default()
+    };
+    assert_eq!(
+        "a\n|-- b\n\\-- c\n    \\-- …\n",
+        a.display_with(options).to_string()
+    );
+}
+
+#[test]
+fn debug_alternate() {
+    let tree = tree!('r' => { 'a' => { 'b', 'c' => { 'd' } }, 'e' });
+    let a = tree.root().first_child().unwrap();
+    assert_eq!(
+        format!("{:#?}", tree!('a' => { 'b', 'c' => { 'd' } })),
+        format!("{a:#?}")
+    );
+    let b = a.first_child().unwrap();
+    assert_eq!("Tree { 'b' }", format!("{b:#?}"));
+    assert_eq!(format!("{tree:#?}"), format!("{:#?}", tree.root()));
+
+    let parsed = ego_tree::Tree::<char>::from_macro_str(&format!("{a:#?}")).unwrap();
+    assert_eq!(format!("{a:#?}"), format!("{parsed:#?}"));
+}
+
+#[test]
+fn debug() {
+    let tree = tree!('a');
+    assert!(format!("{:?}", tree.root()).starts_with("NodeRef { id: NodeId("));
+}
@@ -0,0 +1,129 @@
+use ego_tree::parent_pointer::ParentPointerError;
+use ego_tree::{tree, Tree};
+
+#[test]
+fn from_parent_pointers() {
+    let rows = vec![
+        ("d", Some("b"), 'd'),
+        ("b", Some("a"), 'b'),
+        ("a", None, 'a'),
+        ("c", Some("a"), 'c'),
+        ("e", Some("b"), 'e'),
+    ];
+    let tree = Tree::from_parent_pointers(rows).unwrap();
+    let expected = tree!('a' => { 'b' => { 'd', 'e' }, 'c' });
+    assert_eq!(format!("{:#?}", expected), format!("{:#?}", tree));
+}
+
+#[test]
+fn from_ordered_parent_pointers() {
+    let rows = vec![
+        (4, Some(2), 10, 'd'),
+        (2, Some(1), 5, 'b'),
+        (1, None, 0, 'a'),
+        (3, Some(1), -5, 'c'),
+        (5, Some(2), 10, 'e'),
+        (6, Some(2), 7, 'f'),
+    ];
+    let tree = Tree::from_ordered_parent_pointers(rows).unwrap();
+    let expected = tree!('a' => { 'c', 'b' => { 'f', 'd', 'e' } });
+    assert_eq!(format!("{:#?}", expected), format!("{:#?}", tree));
+}
+
+#[test]
+fn to_parent_pointers() {
+    let tree = tree!('a' => { 'b' => { 'c' }, 'd' });
+    let a = tree.root();
+    let b = a.first_child().unwrap();
+    let c = b.first_child().unwrap();
+    let d = a.last_child().unwrap();
+    assert_eq!(
+        vec![
+            (a.id(), None, &'a'),
+            (b.id(), Some(a.id()), &'b'),
+            (c.id(), Some(b.id()), &'c'),
+            (d.id(), Some(a.id()), &'d'),
+        ],
+        tree.to_parent_pointers()
+    );
+    assert_eq!(
+        vec![
+            (a.id(), None, 0, &'a'),
+            (b.id(), Some(a.id()), 0, &'b'),
+            (c.id(), Some(b.id()), 0, &'c'),
+            (d.id(), Some(a.id()), 1, &'d'),
+        ],
+        tree.to_ordered_parent_pointers()
+    );
+}
+
+#[test]
+fn parent_pointers_round_trip() {
+    let mut tree = tree!('a' => { 'b' => { 'c', 'd' }, 'e' => { 'f' } });
+    let e = tree.root().last_child().unwrap().id();
+    tree.root_mut().prepend_id(e);
+    tree.orphan('x');
+
+    let rows = tree.to_parent_pointers();
+    let parsed = Tree::from_parent_pointers(rows.into_iter().map(|(id, p, v)| (id, p, *v)));
+    let expected = tree!('a' => { 'e' => { 'f' }, 'b' => { 'c', 'd' } });
+    assert_eq!(
+        format!("{:#?}", expected),
+        format!("{:#?}", parsed.unwrap())
+    );
+
+    let rows = tree.to_ordered_parent_pointers();
+    let parsed = Tree::from_ordered_parent_pointers(
+        rows.into_iter()
+            .rev()
+            .map(|(id, p, pos, v)| (id, p, pos, *v)),
+    );
+    assert_eq!(
+        format!("{:#?}", expected),
+        format!("{:#?}", parsed.unwrap())
+    );
+}
+
+#[test]
+fn from_parent_pointers_errors() {
+    let error = |rows: Vec<(u32, Option<u32>, ())>| Tree::from_parent_pointers(rows).unwrap_err();
+
+    assert_eq!(ParentPointerError::Empty, error(vec![]));
+    assert_eq!(
+        ParentPointerError::DuplicateId(2),
+        error(vec![(1, None, ()), (2, Some(1), ()), (2, Some(1), ())])
+    );
+    assert_eq!(
+        ParentPointerError::MultipleRoots(1, 3),
+        error(vec![(1, None, ()), (2, Some(1), ()), (3, None, ())])
+    );
+    assert_eq!(
+        ParentPointerError::MissingParent {
+            id: 2,
+            parent_id: 4
+        },
+        error(vec![(1, None, ()), (2, Some(4), ())])
+    );
+    assert_eq!(
+        ParentPointerError::Cycle(1),
+        error(vec![(1, Some(2), ()), (2, Some(1), ())])
+    );
+    assert_eq!(
+        ParentPointerError::Cycle(3),
+        error(vec![
+            (1, None, ()),
+            (2, Some(1), ()),
+            (3, Some(4), ()),
+            (4, Some(3), ()),
+            (5, Some(4), ()),
+        ])
+    );
+    assert_eq!(
+        ParentPointerError::Cycle(2),
+        error(vec![(1, None, ()), (2, Some(2), ())])
+    );
+    assert_eq!(
+        "missing parent 4 of 2",
+        error(vec![(1, None, ()), (2, Some(4), ())]).to_string()
+    );
+}
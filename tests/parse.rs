@@ -0,0 +1,307 @@
+use ego_tree::{tree, Tree};
+
+#[test]
+fn from_display_str() {
+    let tree = tree! {
+        "root" => {
+            "a" => { "b" => { "c", "d" }, "e" },
+            "f",
+            "g" => { "h" => { "i" } },
+        }
+    };
+    let repr = format!("{tree}");
+    let parsed = Tree::<String>::from_display_str(&repr).unwrap();
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", parsed));
+    assert_eq!(repr, format!("{parsed}"));
+}
+
+#[test]
+fn from_display_str_multi_line_labels() {
+    let trees = [
+        tree!("a" => { "b\nc", "d" }),
+        tree!("a\nb"),
+        tree!("a\nb" => { "c" }),
+        tree!("a" => { "b", "c\nd" => { "e\n\nf" => { "g" } } }),
+        tree!("a" => { "b\n    c", "d\n│   e" }),
+    ];
+    for tree in trees {
+        let repr = format!("{tree}");
+        let parsed = Tree::<String>::from_display_str(&repr).unwrap();
+        assert_eq!(format!("{:#?}", tree), format!("{:#?}", parsed));
+        assert_eq!(repr, format!("{parsed}"));
+    }
+
+    // Continuation lines starting with a branch look like nodes indented too
+    // deep.
+    let tree = tree!("a" => { "b\n└── c" => { "d" } });
+    let error = Tree::<String>::from_display_str(&format!("{tree}")).unwrap_err();
+    assert_eq!("line 3: indentation too deep", error.to_string());
+}
+
+#[test]
+fn from_display_str_root() {
+    let parsed = Tree::<String>::from_display_str("root\n").unwrap();
+    assert_eq!(format!("{:#?}", parsed), format!("{:#?}", tree!("root")));
+}
+
+#[test]
+fn from_display_str_from_str() {
+    let parsed = Tree::<i32>::from_display_str("1\n├── 2\n│   └── 3\n└── 4\n").unwrap();
+    assert_eq!(
+        format!("{:#?}", parsed),
+        format!("{:#?}", tree!(1 => { 2 => { 3 }, 4 }))
+    );
+
+    let err = Tree::<i32>::from_display_str("1\n├── 2\n└── x\n").unwrap_err();
+    assert_eq!(3, err.line());
+    assert_eq!(
+        "line 3: invalid value \"x\": invalid digit found in string",
+        err.to_string()
+    );
+}
+
+#[test]
+fn from_display_str_errors() {
+    let line = |s| Tree::<String>::from_display_str(s).unwrap_err().line();
+
+    // Empty.
+    assert_eq!(1, line(""));
+    // Missing branch.
+    assert_eq!(2, line("root\nchild"));
+    // Too deep.
+    assert_eq!(2, line("root\n    └── a"));
+    // Ancestor indentation doesn't match.
+    assert_eq!(3, line("root\n├── a\n    └── b\n└── c"));
+    assert_eq!(3, line("root\n└── a\n│   └── b"));
+    // Sibling after the last one.
+    assert_eq!(3, line("root\n└── a\n└── b"));
+    // Missing sibling.
+    assert_eq!(2, line("root\n├── a"));
+    assert_eq!(3, line("root\n├── a\n│   ├── b\n└── c"));
+}
+
+#[test]
+fn from_macro_str() {
+    let tree = tree!('a' => { 'b', 'c' => { 'd', 'e' => { 'f' } }, 'g' });
+    let repr = format!("{:#?}", tree);
+    let parsed = Tree::<char>::from_macro_str(&repr).unwrap();
+    assert_eq!(repr, format!("{:#?}", parsed));
+}
+
+#[test]
+fn from_macro_str_round_trip() {
+    let tree = tree! {
+        "root".to_owned() => {
+            "say \"hi\"".to_owned(),
+            "back\\slash, {braces} => arrow".to_owned() => {
+                "new\nline\ttab\0".to_owned(),
+            },
+            "\u{7f} 'quote' é".to_owned(),
+        }
+    };
+    let repr = format!("{:#?}", tree);
+    let parsed = Tree::<String>::from_macro_str(&repr).unwrap();
+    assert_eq!(repr, format!("{:#?}", parsed));
+    assert_eq!("say \"hi\"", parsed.root().first_child().unwrap().value());
+
+    let tree = tree!('\'' => { '"', '\n', '\u{200b}' });
+    let repr = format!("{:#?}", tree);
+    assert_eq!(
+        repr,
+        format!("{:#?}", Tree::<char>::from_macro_str(&repr).unwrap())
+    );
+
+    let tree = tree!(-1.5 => { 2e10, 0.1 });
+    let repr = format!("{:#?}", tree);
+    assert_eq!(
+        repr,
+        format!("{:#?}", Tree::<f64>::from_macro_str(&repr).unwrap())
+    );
+}
+
+#[test]
+fn from_macro_str_macro_syntax() {
+    let parsed = Tree::<i32>::from_macro_str(
+        "
+        1 => {
+            2 => { 3, 4, },
+            5 => { },
+            6,
+        }
+        ",
+    )
+    .unwrap();
+    let expected = tree!(1 => { 2 => { 3, 4 }, 5, 6 });
+    assert_eq!(format!("{:#?}", expected), format!("{:#?}", parsed));
+
+    let parsed = Tree::<String>::from_macro_str("Tree{\"a\"=>{\"b\"}}").unwrap();
+    assert_eq!(
+        format!("{:#?}", tree!("a" => { "b" })),
+        format!("{:#?}", parsed)
+    );
+}
+
+#[test]
+fn from_macro_str_deep() {
+    let depth = 10_000;
+    let repr = format!("0{}{}", " => { 0".repeat(depth), " }".repeat(depth));
+    let parsed = Tree::<u8>::from_macro_str(&repr).unwrap();
+    assert_eq!(depth + 1, parsed.root().descendants().count());
+}
+
+#[test]
+fn from_macro_str_many_lines() {
+    // Lines are only counted to report errors, so this parses in linear time.
+    let lines = 80_000;
+    let repr = format!("0 => {{{}", "\n0,".repeat(lines));
+    let error = Tree::<u8>::from_macro_str(&repr).unwrap_err();
+    assert_eq!(lines + 1, error.line());
+    let parsed = Tree::<u8>::from_macro_str(&format!("{} }}", repr)).unwrap();
+    assert_eq!(lines, parsed.root().children().count());
+}
+
+#[test]
+fn from_macro_str_errors() {
+    let error = |s| Tree::<String>::from_macro_str(s).unwrap_err().to_string();
+
+    assert_eq!("line 1: expected a value", error(""));
+    assert_eq!("line 1: expected a value", error("Tree { }"));
+    assert_eq!("line 2: expected `}`", error("'a' => {\n'b'"));
+    assert_eq!("line 1: expected `}`", error("Tree { 'a'"));
+    assert_eq!("line 1: expected `{`", error("'a' => 'b'"));
+    assert_eq!(
+        "line 1: expected `=>`, `,` or `}`",
+        error("'a' => { 'b' 'c' }")
+    );
+    assert_eq!("line 1: unexpected input after the tree", error("'a', 'b'"));
+    assert_eq!(
+        "line 1: unexpected input after the tree",
+        error("Tree { 'a' } }")
+    );
+    assert_eq!(
+        "line 1: unexpected input after the tree",
+        error("'a' => { 'b' } => { 'c' }")
+    );
+    assert_eq!(
+        "line 1: expected `=>`, `,` or `}`",
+        error("Tree { 'a' => { 'b' } => { 'c' } }")
+    );
+    assert_eq!(
+        "line 1: expected `=>`, `,` or `}`",
+        error("'a' => { 'b' => { 'c' } => { 'd' } }")
+    );
+    assert_eq!("line 2: unterminated literal", error("'a' => {\n\"b }"));
+    assert_eq!("line 1: invalid escape sequence", error(r#""\q""#));
+    assert_eq!(
+        "line 1: invalid value \"x\": invalid digit found in string",
+        Tree::<u8>::from_macro_str("1 => { x }")
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+fn to_sexpr() {
+    let tree = tree!("define" => { "f", "+" => { "x", "1" } , "" });
+    let sexpr = tree.to_sexpr(|node| node.value().to_string());
+    assert_eq!(r#"(define f (+ x 1) "")"#, sexpr);
+    assert_eq!("a", tree!("a").to_sexpr(|node| node.value().to_string()));
+}
+
+#[test]
+fn from_sexpr() {
+    let parsed = Tree::from_sexpr(
+        "
+        ; a comment
+        (define f ; another one
+            (+ x 1))
+        ",
+        |atom| atom.parse::<String>(),
+    )
+    .unwrap();
+    let expected = tree!("define" => { "f", "+" => { "x", "1" } });
+    assert_eq!(format!("{:#?}", expected), format!("{:#?}", parsed));
+
+    let parsed = Tree::from_sexpr("(a(b c)d)", |atom| atom.parse::<String>()).unwrap();
+    let expected = tree!("a" => { "b" => { "c" }, "d" });
+    assert_eq!(format!("{:#?}", expected), format!("{:#?}", parsed));
+
+    let parsed = Tree::from_sexpr("(a)", |atom| atom.parse::<String>()).unwrap();
+    assert_eq!(format!("{:#?}", tree!("a")), format!("{:#?}", parsed));
+}
+
+#[test]
+fn sexpr_round_trip() {
+    let tree = tree! {
+        "root".to_owned() => {
+            "say \"hi\"".to_owned(),
+            "(parens) ; semicolon".to_owned() => {
+                "new\nline\ttab\r\\".to_owned(),
+                "".to_owned(),
+            },
+            "é".to_owned(),
+        }
+    };
+    let sexpr = tree.to_sexpr(|node| node.value().clone());
+    let parsed = Tree::from_sexpr(&sexpr, |atom| atom.parse::<String>()).unwrap();
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", parsed));
+}
+
+#[test]
+fn from_sexpr_deep() {
+    let depth = 10_000;
+    let sexpr = format!("{}0{}", "(0 ".repeat(depth), ")".repeat(depth));
+    let parsed = Tree::from_sexpr(&sexpr, |atom| atom.parse::<u8>()).unwrap();
+    assert_eq!(depth + 1, parsed.root().descendants().count());
+    assert_eq!(sexpr, parsed.to_sexpr(|node| node.value().to_string()));
+}
+
+#[test]
+fn from_sexpr_many_lines() {
+    // Lines are only counted to report errors, so this parses in linear time.
+    let lines = 80_000;
+    let sexpr = format!("{}0", "(0\n".repeat(lines));
+    let error = Tree::from_sexpr(&sexpr, |atom| atom.parse::<u8>()).unwrap_err();
+    assert_eq!(lines + 1, error.line());
+    let sexpr = format!("{}{}", sexpr, ")".repeat(lines));
+    let parsed = Tree::from_sexpr(&sexpr, |atom| atom.parse::<u8>()).unwrap();
+    assert_eq!(lines + 1, parsed.root().descendants().count());
+}
+
+#[test]
+fn from_sexpr_errors() {
+    let error = |s| {
+        Tree::from_sexpr(s, |atom| atom.parse::<String>())
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert_eq!("line 1: expected an expression", error(""));
+    assert_eq!("line 2: expected an expression", error("; only\n"));
+    assert_eq!("line 2: expected `)`", error("(a\nb"));
+    assert_eq!("line 1: unmatched `)`", error(")"));
+    assert_eq!(
+        "line 1: unexpected input after the expression",
+        error("(a b) c")
+    );
+    assert_eq!(
+        "line 1: unexpected input after the expression",
+        error("a b")
+    );
+    assert_eq!(
+        "line 1: expected an atom at the head of a list",
+        error("()")
+    );
+    assert_eq!(
+        "line 1: expected an atom at the head of a list",
+        error("((a) b)")
+    );
+    assert_eq!("line 2: unterminated string", error("(a\n\"b)"));
+    assert_eq!("line 1: invalid escape sequence", error(r#""\q""#));
+    assert_eq!(
+        "line 1: invalid atom \"x\": invalid digit found in string",
+        Tree::from_sexpr("(1 x)", |atom| atom.parse::<u8>())
+            .unwrap_err()
+            .to_string()
+    );
+}
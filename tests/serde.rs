@@ -0,0 +1,248 @@
+#![cfg(feature = "serde")]
+
+use ego_tree::serde::{flat, MAX_DEPTH};
+use ego_tree::{tree, NodeId, Tree};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn serialize() {
+    let tree = tree!('a' => { 'b', 'c' => { 'd' } });
+    let json = serde_json::to_string(&tree).unwrap();
+    assert_eq!(
+        json,
+        concat!(
+            r#"{"value":"a","children":["#,
+            r#"{"value":"b","children":[]},"#,
+            r#"{"value":"c","children":[{"value":"d","children":[]}]}"#,
+            r#"]}"#,
+        )
+    );
+}
+
+#[test]
+fn serialize_subtree() {
+    let tree = tree!('a' => { 'b', 'c' => { 'd' } });
+    let c = tree.root().last_child().unwrap();
+    let json = serde_json::to_string(&c).unwrap();
+    assert_eq!(
+        json,
+        r#"{"value":"c","children":[{"value":"d","children":[]}]}"#
+    );
+}
+
+#[test]
+fn serialize_skips_orphans() {
+    let mut tree = tree!('a' => { 'b' });
+    tree.orphan('x');
+    let json = serde_json::to_string(&tree).unwrap();
+    assert_eq!(
+        json,
+        r#"{"value":"a","children":[{"value":"b","children":[]}]}"#
+    );
+}
+
+#[test]
+fn round_trip() {
+    let tree = tree! {
+        "root" => {
+            "a" => { "b", "c" => { "d" } },
+            "e",
+            "f" => { "g" },
+        }
+    };
+    let json = serde_json::to_string(&tree).unwrap();
+    let de: Tree<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", de));
+    assert_eq!(json, serde_json::to_string(&de).unwrap());
+
+    let g = de.root().last_child().unwrap().first_child().unwrap();
+    assert_eq!("g", g.value());
+    assert_eq!(Some(de.root()), g.parent().unwrap().parent());
+}
+
+#[test]
+fn deserialize_field_order() {
+    let json = r#"{"children":[{"value":2},{"children":[],"value":3}],"value":1}"#;
+    let tree: Tree<i32> = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        format!("{:#?}", tree),
+        format!("{:#?}", tree!(1 => { 2, 3 }))
+    );
+}
+
+#[test]
+fn deserialize_errors() {
+    assert!(serde_json::from_str::<Tree<i32>>(r#"{"children":[]}"#).is_err());
+    assert!(serde_json::from_str::<Tree<i32>>(r#"{"value":1,"value":2}"#).is_err());
+    assert!(serde_json::from_str::<Tree<i32>>(r#"{"value":1,"other":2}"#).is_err());
+    assert!(serde_json::from_str::<Tree<i32>>(r#"{"value":1,"children":{}}"#).is_err());
+}
+
+#[test]
+fn node_id() {
+    let tree = tree!('a' => { 'b' });
+    let b = tree.root().first_child().unwrap().id();
+    let json = serde_json::to_string(&b).unwrap();
+    assert_eq!(json, "1");
+    assert_eq!(b, serde_json::from_str::<NodeId>(&json).unwrap());
+    assert!(serde_json::from_str::<NodeId>("18446744073709551615").is_err());
+}
+
+#[derive(Serialize, Deserialize)]
+struct Document {
+    #[serde(with = "flat")]
+    tree: Tree<String>,
+    selected: NodeId,
+}
+
+#[test]
+fn flat_round_trip() {
+    let mut tree = tree! {
+        "root".to_owned() => {
+            "a".to_owned() => { "b".to_owned(), "c".to_owned() },
+            "d".to_owned(),
+        }
+    };
+    let c = tree
+        .root()
+        .first_child()
+        .unwrap()
+        .last_child()
+        .unwrap()
+        .id();
+    let d = tree.root().last_child().unwrap().id();
+    tree.get_mut(d).unwrap().detach();
+    tree.root_mut().append_id(c);
+    let orphan = tree.orphan("orphan".to_owned()).id();
+    tree.get_mut(orphan).unwrap().append("child".to_owned());
+
+    let doc = Document { tree, selected: c };
+    let json = serde_json::to_string(&doc).unwrap();
+    let de: Document = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(doc.tree, de.tree);
+    assert_eq!(doc.selected, de.selected);
+    assert_eq!("c", de.tree.get(de.selected).unwrap().value());
+    assert_eq!("d", de.tree.get(d).unwrap().value());
+    assert_eq!(None, de.tree.get(d).unwrap().parent());
+    assert_eq!(
+        "child",
+        de.tree.get(orphan).unwrap().first_child().unwrap().value()
+    );
+}
+
+fn from_flat(json: &str) -> Result<Tree<char>, serde_json::Error> {
+    flat::deserialize(&mut serde_json::Deserializer::from_str(json))
+}
+
+#[test]
+fn flat_validation() {
+    let leaf = |value: char, parent: Option<usize>, prev: Option<usize>, next: Option<usize>| {
+        serde_json::json!({
+            "parent": parent,
+            "prev_sibling": prev,
+            "next_sibling": next,
+            "children": null,
+            "value": value,
+        })
+    };
+    let valid = serde_json::json!([
+        { "parent": null, "prev_sibling": null, "next_sibling": null, "children": [1, 2], "value": 'a' },
+        leaf('b', Some(0), None, Some(2)),
+        leaf('c', Some(0), Some(1), None),
+    ]);
+    let tree = from_flat(&valid.to_string()).unwrap();
+    assert_eq!(
+        format!("{:#?}", tree),
+        format!("{:#?}", tree!('a' => { 'b', 'c' }))
+    );
+
+    // Empty.
+    assert!(from_flat("[]").is_err());
+
+    // Out of bounds.
+    let mut json = valid.clone();
+    json[2]["prev_sibling"] = 3.into();
+    assert!(from_flat(&json.to_string()).is_err());
+
+    // Asymmetric siblings.
+    let mut json = valid.clone();
+    json[2]["prev_sibling"] = serde_json::Value::Null;
+    assert!(from_flat(&json.to_string()).is_err());
+
+    // Child missing from its parent.
+    let mut json = valid.clone();
+    json[0]["children"] = serde_json::json!([1, 1]);
+    json[1]["next_sibling"] = serde_json::Value::Null;
+    assert!(from_flat(&json.to_string()).is_err());
+
+    // Parent cycle.
+    let json = serde_json::json!([
+        leaf('a', None, None, None),
+        { "parent": 2, "prev_sibling": null, "next_sibling": null, "children": [2, 2], "value": 'b' },
+        { "parent": 1, "prev_sibling": null, "next_sibling": null, "children": [1, 1], "value": 'c' },
+    ]);
+    assert!(from_flat(&json.to_string()).is_err());
+
+    // Sibling cycle.
+    let json = serde_json::json!([
+        { "parent": null, "prev_sibling": null, "next_sibling": null, "children": [1, 1], "value": 'a' },
+        leaf('b', Some(0), None, None),
+        leaf('c', Some(0), Some(3), Some(3)),
+        leaf('d', Some(0), Some(2), Some(2)),
+    ]);
+    assert!(from_flat(&json.to_string()).is_err());
+}
+
+fn chain(depth: usize) -> Tree<u32> {
+    let mut tree = Tree::new(0);
+    let mut id = tree.root().id();
+    for value in 1..=depth as u32 {
+        id = tree.get_mut(id).unwrap().append(value).id();
+    }
+    tree
+}
+
+/// Builds a nested JSON value for a chain, without the recursion limit of
+/// `serde_json::from_str`.
+fn chain_json(depth: usize) -> serde_json::Value {
+    let mut json = serde_json::json!({ "value": depth, "children": [] });
+    for value in (0..depth).rev() {
+        json = serde_json::json!({ "value": value, "children": [json] });
+    }
+    json
+}
+
+#[test]
+fn nested_max_depth() {
+    let tree = chain(MAX_DEPTH);
+    let json = serde_json::to_value(&tree).unwrap();
+    let de = Tree::<u32>::deserialize(json).unwrap();
+    assert!(de.structural_eq(&tree));
+
+    // Only deserialization is bounded.
+    let json = serde_json::to_value(chain(MAX_DEPTH + 1)).unwrap();
+    assert_eq!(chain_json(MAX_DEPTH + 1), json);
+    let de = Tree::<u32>::deserialize(json);
+    assert!(de.unwrap_err().to_string().contains("tree deeper than"));
+}
+
+#[test]
+fn nested_json_depth() {
+    // Each level takes two levels of `serde_json`'s recursion limit.
+    let json = serde_json::to_string(&chain(62)).unwrap();
+    assert!(serde_json::from_str::<Tree<u32>>(&json).is_ok());
+    let json = serde_json::to_string(&chain(63)).unwrap();
+    let error = serde_json::from_str::<Tree<u32>>(&json).unwrap_err();
+    assert!(error.to_string().contains("recursion limit exceeded"));
+}
+
+#[test]
+fn flat_deep() {
+    let tree = chain(200_000);
+    let mut json = Vec::new();
+    flat::serialize(&tree, &mut serde_json::Serializer::new(&mut json)).unwrap();
+    let de: Tree<u32> =
+        flat::deserialize(&mut serde_json::Deserializer::from_slice(&json)).unwrap();
+    assert_eq!(de, tree);
+}
@@ -0,0 +1,184 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+use ego_tree::{tree, NodeMap, StructuralHash, Tree};
+
+fn hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn rebuilt() -> Tree<char> {
+    // Same shape as `tree!('a' => { 'b' => { 'c' }, 'd' })`, built in a
+    // different order and with garbage.
+    let mut tree = Tree::new('a');
+    tree.orphan('x');
+    let d = tree.root_mut().append('d').id();
+    let mut b = tree.orphan('b');
+    b.append('c');
+    let b = b.id();
+    tree.get_mut(d).unwrap().insert_id_before(b);
+    tree.root_mut().append('y').detach();
+    tree
+}
+
+#[test]
+fn structural_eq() {
+    let tree = tree!('a' => { 'b' => { 'c' }, 'd' });
+    let rebuilt = rebuilt();
+    assert_ne!(tree, rebuilt);
+    assert!(tree.structural_eq(&rebuilt));
+    assert!(rebuilt.structural_eq(&tree));
+
+    assert!(!tree.structural_eq(&tree!('a' => { 'b', 'c', 'd' })));
+    assert!(!tree.structural_eq(&tree!('a' => { 'b' => { 'c' } })));
+    assert!(!tree.structural_eq(&tree!('a' => { 'b' => { 'c' }, 'e' })));
+    assert!(!tree.structural_eq(&tree!('a' => { 'b' => { 'c', 'd' } })));
+}
+
+#[test]
+fn subtree_eq() {
+    let tree = tree!('r' => { 'a' => { 'b' }, 'x' => { 'a' => { 'b' } }, 'a' });
+    let first = tree.root().first_child().unwrap();
+    let nested = first.next_sibling().unwrap().first_child().unwrap();
+    let last = tree.root().last_child().unwrap();
+    assert!(first.subtree_eq(&nested));
+    assert!(first != nested);
+    assert!(!first.subtree_eq(&last));
+
+    let other = tree!('a' => { 'b' });
+    assert!(other.root().subtree_eq(&first));
+}
+
+#[test]
+fn structural_hash() {
+    let tree = tree!('a' => { 'b' => { 'c' }, 'd' });
+    let rebuilt = rebuilt();
+    assert_eq!(
+        StructuralHash(tree.clone()),
+        StructuralHash(rebuilt.clone())
+    );
+    assert_eq!(
+        hash(&StructuralHash(tree.clone())),
+        hash(&StructuralHash(rebuilt))
+    );
+    assert_ne!(
+        hash(&StructuralHash(tree.clone())),
+        hash(&StructuralHash(tree!('a' => { 'b', 'c', 'd' })))
+    );
+
+    let mut set = HashSet::new();
+    set.insert(StructuralHash(tree));
+    set.insert(StructuralHash(tree!('a' => { 'b' => { 'c' } , 'd' })));
+    set.insert(StructuralHash(tree!('a' => { 'b' => { 'c', 'd' } })));
+    assert_eq!(2, set.len());
+}
+
+#[test]
+fn subtree_hash() {
+    let tree = tree!('r' => { 'a' => { 'b', 'c' }, 'x' => { 'a' => { 'b', 'c' } } });
+    let first = tree.root().first_child().unwrap();
+    let second = tree.root().last_child().unwrap().first_child().unwrap();
+    assert_eq!(
+        first.subtree_hash::<DefaultHasher>(),
+        second.subtree_hash::<DefaultHasher>()
+    );
+    assert_ne!(
+        first.subtree_hash::<DefaultHasher>(),
+        tree.root().subtree_hash::<DefaultHasher>()
+    );
+
+    // Shape matters, not only values in order.
+    let flat = tree!('a' => { 'b', 'c' });
+    let deep = tree!('a' => { 'b' => { 'c' } });
+    assert_ne!(
+        flat.root().subtree_hash::<DefaultHasher>(),
+        deep.root().subtree_hash::<DefaultHasher>()
+    );
+    assert_eq!(
+        rebuilt().root().subtree_hash::<DefaultHasher>(),
+        tree!('a' => { 'b' => { 'c' }, 'd' })
+            .root()
+            .subtree_hash::<DefaultHasher>()
+    );
+}
+
+#[test]
+fn subtree_hashes() {
+    let mut tree = tree!('r' => { 'a' => { 'b' }, 'c' => { 'a' => { 'b' } }, 'd' });
+    tree.orphan('o');
+    let hashes = tree.subtree_hashes();
+    assert_eq!(hashes.len(), 7);
+    for node in tree.root().descendants() {
+        assert_eq!(hashes[node.id()], node.subtree_hash::<DefaultHasher>());
+    }
+
+    // Finds duplicate subtrees.
+    let mut seen = HashSet::new();
+    let duplicates: Vec<char> = tree
+        .root()
+        .descendants()
+        .filter(|node| !seen.insert(hashes[node.id()]))
+        .map(|node| *node.value())
+        .collect();
+    assert_eq!(duplicates, ['a', 'b']);
+
+    // Changing a node changes the hashes of its ancestors only.
+    let d = tree.root().last_child().unwrap().id();
+    let b = tree
+        .root()
+        .first_child()
+        .unwrap()
+        .first_child()
+        .unwrap()
+        .id();
+    *tree.get_mut(b).unwrap().value() = 'e';
+    let changed = tree.subtree_hashes();
+    let differing: NodeMap<()> = tree
+        .root()
+        .descendants()
+        .filter(|node| changed[node.id()] != hashes[node.id()])
+        .map(|node| (node.id(), ()))
+        .collect();
+    assert_eq!(differing.len(), 3);
+    assert!(differing.contains_key(b));
+    assert!(!differing.contains_key(d));
+}
+
+#[test]
+fn node_map() {
+    let mut tree = tree!('a' => { 'b', 'c' });
+    let b = tree.root().first_child().unwrap().id();
+    let c = tree.root().last_child().unwrap().id();
+    let mut map = NodeMap::new();
+    assert!(map.is_empty());
+    assert_eq!(map.insert(c, 1), None);
+    assert_eq!(map.insert(c, 2), Some(1));
+    assert_eq!(map.get(b), None);
+    *map.get_mut(c).unwrap() += 1;
+    assert_eq!(map[c], 3);
+    map.insert(tree.root().id(), 0);
+    let entries: Vec<_> = map.iter().collect();
+    assert_eq!(entries, [(tree.root().id(), &0), (c, &3)]);
+    assert_eq!(map.remove(c), Some(3));
+    assert_eq!(map.remove(c), None);
+    assert_eq!(map.len(), 1);
+
+    // Equality and hashing ignore removed entries.
+    let mut other = NodeMap::new();
+    other.insert(c, 1);
+    other.remove(c);
+    assert_eq!(other, NodeMap::new());
+    assert_eq!(hash(&other), hash(&NodeMap::<i32>::new()));
+    other.insert(tree.root().id(), 0);
+    assert_eq!(other, map);
+    assert_eq!(hash(&other), hash(&map));
+    other.insert(b, 0);
+    assert_ne!(other, map);
+
+    // IDs past the end of the map are missing, not out of bounds.
+    let d = tree.root_mut().append('d').id();
+    assert!(!map.contains_key(d));
+}
@@ -1,6 +1,6 @@
 extern crate ego_tree;
 
-use ego_tree::{tree, Tree};
+use ego_tree::{tree, SwapError, Tree};
 
 #[test]
 fn new() {
@@ -70,6 +70,97 @@ fn neq() {
     assert_eq!(one, two);
 }
 
+#[test]
+fn swap_nodes() {
+    let mut tree = tree!('a' => { 'b' => { 'c', 'd' }, 'e' => { 'f' => { 'g' }, 'h' } });
+    let d = tree
+        .root()
+        .first_child()
+        .unwrap()
+        .last_child()
+        .unwrap()
+        .id();
+    let f = tree
+        .root()
+        .last_child()
+        .unwrap()
+        .first_child()
+        .unwrap()
+        .id();
+    tree.swap_nodes(d, f).unwrap();
+
+    let expected = tree!('a' => { 'b' => { 'c', 'f' => { 'g' } }, 'e' => { 'd', 'h' } });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+    assert_eq!(
+        &'f',
+        tree.root()
+            .first_child()
+            .unwrap()
+            .last_child()
+            .unwrap()
+            .value()
+    );
+    assert_eq!(
+        &'d',
+        tree.root()
+            .last_child()
+            .unwrap()
+            .first_child()
+            .unwrap()
+            .value()
+    );
+}
+
+#[test]
+fn swap_nodes_siblings() {
+    let mut tree = tree!('a' => { 'b', 'c', 'd', 'e' });
+    let ids: Vec<_> = tree.root().children().map(|node| node.id()).collect();
+
+    tree.swap_nodes(ids[1], ids[2]).unwrap();
+    let expected = tree!('a' => { 'b', 'd', 'c', 'e' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+
+    tree.swap_nodes(ids[3], ids[0]).unwrap();
+    let expected = tree!('a' => { 'e', 'd', 'c', 'b' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+    let values: Vec<_> = tree
+        .root()
+        .children()
+        .rev()
+        .map(|node| *node.value())
+        .collect();
+    assert_eq!(vec!['b', 'c', 'd', 'e'], values);
+
+    tree.swap_nodes(ids[0], ids[0]).unwrap();
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+}
+
+#[test]
+fn swap_nodes_orphan() {
+    let mut tree = tree!('a' => { 'b', 'c' });
+    let c = tree.root().last_child().unwrap().id();
+    let x = tree.orphan('x').id();
+    tree.swap_nodes(c, x).unwrap();
+
+    let expected = tree!('a' => { 'b', 'x' });
+    assert_eq!(format!("{:#?}", tree), format!("{:#?}", expected));
+    assert_eq!(None, tree.get(c).unwrap().parent());
+}
+
+#[test]
+fn swap_nodes_ancestor() {
+    let mut tree = tree!('a' => { 'b' => { 'c' } });
+    let b = tree.root().first_child().unwrap().id();
+    let c = tree.get(b).unwrap().first_child().unwrap().id();
+
+    let err = SwapError {
+        ancestor: b,
+        descendant: c,
+    };
+    assert_eq!(Err(err), tree.swap_nodes(b, c));
+    assert_eq!(Err(err), tree.swap_nodes(c, b));
+}
+
 #[test]
 fn insert_id_after() {
     let mut tree = tree! {